@@ -0,0 +1,162 @@
+use crate::{AclintDevice, NotConfiguredError, MSWI, MTIMER, SSWI};
+
+/// The three devices of an ACLINT, each at its own base address.
+///
+/// Operations on an unconfigured device return [`NotConfiguredError`].
+#[derive(Clone, Copy)]
+pub struct Aclint<'a, const N: usize> {
+    mswi: Option<&'a MSWI<N>>,
+    mtimer: Option<&'a MTIMER<N>>,
+    sswi: Option<&'a SSWI<N>>,
+}
+
+/// Builds an [`Aclint`] from the devices present on the platform.
+///
+/// Device references usually come from `from_base_addr` of each device type.
+#[derive(Clone, Copy)]
+pub struct AclintBuilder<'a, const N: usize>(Aclint<'a, N>);
+
+impl<'a, const N: usize> AclintBuilder<'a, N> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Aclint {
+            mswi: None,
+            mtimer: None,
+            sswi: None,
+        })
+    }
+
+    #[inline]
+    pub const fn mswi(mut self, mswi: &'a MSWI<N>) -> Self {
+        self.0.mswi = Some(mswi);
+        self
+    }
+
+    #[inline]
+    pub const fn mtimer(mut self, mtimer: &'a MTIMER<N>) -> Self {
+        self.0.mtimer = Some(mtimer);
+        self
+    }
+
+    #[inline]
+    pub const fn sswi(mut self, sswi: &'a SSWI<N>) -> Self {
+        self.0.sswi = Some(sswi);
+        self
+    }
+
+    #[inline]
+    pub const fn build(self) -> Aclint<'a, N> {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for AclintBuilder<'_, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, const N: usize> Aclint<'a, N> {
+    #[inline]
+    pub fn mswi(&self) -> Result<&'a MSWI<N>, NotConfiguredError> {
+        self.mswi.ok_or(NotConfiguredError(AclintDevice::Mswi))
+    }
+
+    #[inline]
+    pub fn mtimer(&self) -> Result<&'a MTIMER<N>, NotConfiguredError> {
+        self.mtimer.ok_or(NotConfiguredError(AclintDevice::Mtimer))
+    }
+
+    #[inline]
+    pub fn sswi(&self) -> Result<&'a SSWI<N>, NotConfiguredError> {
+        self.sswi.ok_or(NotConfiguredError(AclintDevice::Sswi))
+    }
+
+    #[inline]
+    pub fn set_msip(&self, hart_idx: usize) -> Result<(), NotConfiguredError> {
+        self.mswi().map(|mswi| mswi.set_msip(hart_idx))
+    }
+
+    #[inline]
+    pub fn set_setssip(&self, hart_idx: usize) -> Result<(), NotConfiguredError> {
+        self.sswi().map(|sswi| sswi.set_setssip(hart_idx))
+    }
+
+    #[inline]
+    pub fn read_mtime(&self) -> Result<u64, NotConfiguredError> {
+        self.mtimer().map(|mtimer| mtimer.read_mtime())
+    }
+
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) -> Result<(), NotConfiguredError> {
+        self.mtimer()
+            .map(|mtimer| mtimer.write_mtimecmp(hart_idx, val))
+    }
+
+    /// Raises the machine timer, machine software and supervisor software interrupts
+    /// of `hart_idx` together, by setting `msip` and `setssip` and writing `mtimecmp` to `0`.
+    ///
+    /// This is a diagnostic for interrupt controller bring-up and tests:
+    /// the interrupts fire at once wherever they are enabled,
+    /// and the previous `mtimecmp` is lost.
+    /// Fails with [`NotConfiguredError`] without touching any register
+    /// unless all three devices are configured.
+    pub fn assert_all(&self, hart_idx: usize) -> Result<(), NotConfiguredError> {
+        let (mswi, mtimer, sswi) = (self.mswi()?, self.mtimer()?, self.sswi()?);
+        mtimer.write_mtimecmp(hart_idx, 0);
+        mswi.set_msip(hart_idx);
+        sswi.set_setssip(hart_idx);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_aclint() {
+    let mswi = unsafe { MSWI::<4>::new_boxed() };
+    let mtimer = unsafe { MTIMER::<4>::new_boxed() };
+    let sswi = unsafe { SSWI::<4>::new_boxed() };
+    let aclint = AclintBuilder::new()
+        .mswi(&mswi)
+        .mtimer(&mtimer)
+        .sswi(&sswi)
+        .build();
+    aclint.set_msip(1).unwrap();
+    aclint.set_setssip(2).unwrap();
+    aclint.write_mtimecmp(3, 7).unwrap();
+    mtimer.write_mtime(5);
+    assert!(mswi.read_msip(1));
+    assert!(sswi.read_setssip(2));
+    assert_eq!(mtimer.read_mtimecmp(3), 7);
+    assert_eq!(aclint.read_mtime(), Ok(5));
+
+    let aclint = AclintBuilder::new().mswi(&mswi).build();
+    assert_eq!(
+        aclint.set_setssip(0),
+        Err(NotConfiguredError(AclintDevice::Sswi))
+    );
+}
+
+#[test]
+fn test_assert_all() {
+    let mswi = unsafe { MSWI::<4>::new_boxed() };
+    let mtimer = unsafe { MTIMER::<4>::new_boxed() };
+    let sswi = unsafe { SSWI::<4>::new_boxed() };
+    mtimer.write_mtimecmp(2, 7);
+    let aclint = AclintBuilder::new().mswi(&mswi).mtimer(&mtimer).build();
+    assert_eq!(
+        aclint.assert_all(2),
+        Err(NotConfiguredError(AclintDevice::Sswi))
+    );
+    assert!(!mswi.read_msip(2));
+
+    let aclint = AclintBuilder::new()
+        .mswi(&mswi)
+        .mtimer(&mtimer)
+        .sswi(&sswi)
+        .build();
+    aclint.assert_all(2).unwrap();
+    assert!(mswi.read_msip(2));
+    assert!(sswi.read_setssip(2));
+    assert_eq!(mtimer.read_mtimecmp(2), 0);
+}
@@ -0,0 +1,21 @@
+//! Well-known base addresses of CLINT and ACLINT devices.
+//!
+//! Multi-socket platforms place the devices of socket 0 at these addresses.
+
+/// SiFive CLINT of QEMU `virt` machine.
+pub const QEMU_VIRT_CLINT: usize = 0x0200_0000;
+
+/// ACLINT MSWI of QEMU `virt` machine started with `aclint=on`.
+pub const QEMU_VIRT_ACLINT_MSWI: usize = 0x0200_0000;
+
+/// ACLINT MTIMER of QEMU `virt` machine started with `aclint=on`.
+pub const QEMU_VIRT_ACLINT_MTIMER: usize = 0x0200_4000;
+
+/// ACLINT SSWI of QEMU `virt` machine started with `aclint=on`.
+pub const QEMU_VIRT_ACLINT_SSWI: usize = 0x02f0_0000;
+
+/// CLINT of SiFive FU540-C000, see the FU540-C000 manual, chapter 4 "Memory Map".
+pub const SIFIVE_FU540_CLINT: usize = 0x0200_0000;
+
+/// CLINT of SiFive FU740-C000, see the FU740-C000 manual, chapter 4 "Memory Map".
+pub const SIFIVE_FU740_CLINT: usize = 0x0200_0000;
@@ -0,0 +1,170 @@
+use crate::{AclintMtimer, ClintOver, MmioAccess, Rv32Mtimer, SifiveClint, MSWI, MTIMER};
+
+/// Timer operations of a CLINT or an ACLINT MTIMER,
+/// for drivers that need no software interrupts.
+///
+/// Implemented by [`MTIMER`], [`Rv32Mtimer`], [`AclintMtimer`], [`SifiveClint`] and [`ClintOver`],
+/// all by forwarding to their inherent methods,
+/// and with the `std` feature by the host model `sim::SimMtimer`.
+pub trait MtimerOps {
+    fn read_mtime(&self) -> u64;
+    fn write_mtime(&self, val: u64);
+    fn read_mtimecmp(&self, hart_idx: usize) -> u64;
+    fn write_mtimecmp(&self, hart_idx: usize, val: u64);
+
+    /// Returns whether the deadline of `hart_idx` has passed,
+    /// like [`MTIMER::is_timer_expired`].
+    #[inline]
+    fn is_timer_expired(&self, hart_idx: usize) -> bool {
+        self.read_mtimecmp(hart_idx) <= self.read_mtime()
+    }
+
+    /// Returns the ticks left until the deadline of `hart_idx`, or `None` if it has passed,
+    /// like [`MTIMER::ticks_until_deadline`].
+    #[inline]
+    fn ticks_until_deadline(&self, hart_idx: usize) -> Option<u64> {
+        let mtime = self.read_mtime();
+        let mtimecmp = self.read_mtimecmp(hart_idx);
+        (mtimecmp > mtime).then(|| mtimecmp - mtime)
+    }
+}
+
+/// Machine software interrupt operations of a CLINT or an ACLINT MSWI.
+///
+/// Implemented by [`MSWI`], [`SifiveClint`] and [`ClintOver`], all by forwarding to their inherent methods.
+/// [`SSWI`](crate::SSWI) does not implement it, since `setssip` can neither be read back nor cleared.
+pub trait SoftIntOps {
+    fn read_msip(&self, hart_idx: usize) -> bool;
+    fn set_msip(&self, hart_idx: usize);
+    fn clear_msip(&self, hart_idx: usize);
+}
+
+/// Operations of a CLINT, for code that should also run against a test double.
+///
+/// Implemented for every type implementing both [`MtimerOps`] and [`SoftIntOps`],
+/// such as [`SifiveClint`].
+pub trait Clint: MtimerOps + SoftIntOps {}
+
+impl<T: MtimerOps + SoftIntOps + ?Sized> Clint for T {}
+
+macro_rules! impl_mtimer_ops {
+    ($($ty:ty $(, $n:ident)?;)*) => {$(
+        impl$(<const $n: usize>)? MtimerOps for $ty {
+            #[inline]
+            fn read_mtime(&self) -> u64 {
+                <$ty>::read_mtime(self)
+            }
+
+            #[inline]
+            fn write_mtime(&self, val: u64) {
+                <$ty>::write_mtime(self, val)
+            }
+
+            #[inline]
+            fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+                <$ty>::read_mtimecmp(self, hart_idx)
+            }
+
+            #[inline]
+            fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+                <$ty>::write_mtimecmp(self, hart_idx, val)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_soft_int_ops {
+    ($($ty:ty, $n:ident;)*) => {$(
+        impl<const $n: usize> SoftIntOps for $ty {
+            #[inline]
+            fn read_msip(&self, hart_idx: usize) -> bool {
+                <$ty>::read_msip(self, hart_idx)
+            }
+
+            #[inline]
+            fn set_msip(&self, hart_idx: usize) {
+                <$ty>::set_msip(self, hart_idx)
+            }
+
+            #[inline]
+            fn clear_msip(&self, hart_idx: usize) {
+                <$ty>::clear_msip(self, hart_idx)
+            }
+        }
+    )*};
+}
+
+impl_mtimer_ops! {
+    MTIMER<N>, N;
+    Rv32Mtimer<N>, N;
+    SifiveClint<H>, H;
+    AclintMtimer;
+}
+
+impl_soft_int_ops! {
+    MSWI<N>, N;
+    SifiveClint<H>, H;
+}
+
+impl<A: MmioAccess, const H: usize> MtimerOps for ClintOver<A, H> {
+    #[inline]
+    fn read_mtime(&self) -> u64 {
+        ClintOver::read_mtime(self)
+    }
+
+    #[inline]
+    fn write_mtime(&self, val: u64) {
+        ClintOver::write_mtime(self, val)
+    }
+
+    #[inline]
+    fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+        ClintOver::read_mtimecmp(self, hart_idx)
+    }
+
+    #[inline]
+    fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+        ClintOver::write_mtimecmp(self, hart_idx, val)
+    }
+}
+
+impl<A: MmioAccess, const H: usize> SoftIntOps for ClintOver<A, H> {
+    #[inline]
+    fn read_msip(&self, hart_idx: usize) -> bool {
+        ClintOver::read_msip(self, hart_idx)
+    }
+
+    #[inline]
+    fn set_msip(&self, hart_idx: usize) {
+        ClintOver::set_msip(self, hart_idx)
+    }
+
+    #[inline]
+    fn clear_msip(&self, hart_idx: usize) {
+        ClintOver::clear_msip(self, hart_idx)
+    }
+}
+
+#[test]
+fn test_clint_trait() {
+    fn arm<C: Clint>(clint: &C, hart_idx: usize) {
+        clint.write_mtimecmp(hart_idx, clint.read_mtime() + 10);
+        clint.set_msip(hart_idx);
+    }
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    clint.write_mtime(5);
+    arm(&*clint, 1);
+    assert_eq!(clint.read_mtimecmp(1), 15);
+    assert!(clint.read_msip(1));
+}
+
+#[test]
+fn test_mtimer_ops() {
+    fn arm<T: MtimerOps + ?Sized>(timer: &T, hart_idx: usize) {
+        timer.write_mtimecmp(hart_idx, timer.read_mtime() + 10);
+    }
+    let mtimer = unsafe { MTIMER::<2>::new_boxed() };
+    mtimer.write_mtime(5);
+    arm(&*mtimer, 1);
+    assert_eq!(mtimer.read_mtimecmp(1), 15);
+}
@@ -0,0 +1,109 @@
+use crate::{elapsed_ticks, SifiveClint, Timebase};
+use core::time::Duration;
+
+/// [`Duration`]-based view of the timer of a CLINT whose `mtime` counts at a known frequency.
+///
+/// Uptime is `mtime` converted to time, so it starts from whenever `mtime` was last reset.
+#[derive(Clone, Copy)]
+pub struct ClintClock<'a, const H: usize> {
+    clint: &'a SifiveClint<H>,
+    timebase: Timebase,
+}
+
+impl<'a, const H: usize> ClintClock<'a, H> {
+    /// Creates a clock counting `mtime` at `freq_hz`, which must not be zero.
+    #[inline]
+    pub const fn new(clint: &'a SifiveClint<H>, freq_hz: u64) -> Self {
+        Self {
+            clint,
+            timebase: Timebase::new(freq_hz),
+        }
+    }
+
+    #[inline]
+    pub const fn timebase(&self) -> Timebase {
+        self.timebase
+    }
+
+    /// Reads `mtime` as the time since it started counting.
+    #[inline]
+    pub fn read_uptime(&self) -> Duration {
+        self.timebase.ticks_to_duration(self.clint.read_mtime())
+    }
+
+    /// Sets the timer of `hart_idx` to fire once the uptime reaches `at`.
+    #[inline]
+    pub fn set_deadline(&self, hart_idx: usize, at: Duration) {
+        self.clint
+            .write_mtimecmp(hart_idx, self.timebase.duration_to_ticks(at))
+    }
+
+    /// Sets the timer of `hart_idx` to fire `after` from now, saturating at `u64::MAX` ticks.
+    #[inline]
+    pub fn set_timer_in(&self, hart_idx: usize, after: Duration) {
+        self.clint
+            .set_mtimecmp_after_saturating(hart_idx, self.timebase.duration_to_ticks(after))
+    }
+}
+
+/// A sample of `mtime`, returned by [`SifiveClint::now`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClintInstant(u64);
+
+impl ClintInstant {
+    /// Returns the sampled `mtime`.
+    #[inline]
+    pub const fn ticks(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the ticks from this instant to the current `mtime` of `clint`.
+    #[inline]
+    pub fn elapsed<const H: usize>(&self, clint: &SifiveClint<H>) -> u64 {
+        elapsed_ticks(self.0, clint.read_mtime())
+    }
+
+    /// Like [`elapsed`](Self::elapsed), converted into a [`Duration`] at `timebase`.
+    #[inline]
+    pub fn elapsed_duration<const H: usize>(
+        &self,
+        clint: &SifiveClint<H>,
+        timebase: Timebase,
+    ) -> Duration {
+        timebase.ticks_to_duration(self.elapsed(clint))
+    }
+}
+
+impl<const H: usize> SifiveClint<H> {
+    /// Samples `mtime` as an instant to measure elapsed time from.
+    #[inline]
+    pub fn now(&self) -> ClintInstant {
+        ClintInstant(self.read_mtime())
+    }
+}
+
+#[test]
+fn test_clock() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    let clock = ClintClock::new(&clint, 10_000_000);
+    clint.write_mtime(15_000_000);
+    assert_eq!(clock.read_uptime(), Duration::from_millis(1500));
+    clock.set_deadline(0, Duration::from_secs(2));
+    assert_eq!(clint.read_mtimecmp(0), 20_000_000);
+    clock.set_timer_in(1, Duration::from_millis(5));
+    assert_eq!(clint.read_mtimecmp(1), 15_050_000);
+}
+
+#[test]
+fn test_instant() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    clint.write_mtime(100);
+    let t = clint.now();
+    clint.write_mtime(350);
+    assert_eq!(t.ticks(), 100);
+    assert_eq!(t.elapsed(&clint), 250);
+    assert_eq!(
+        t.elapsed_duration(&clint, Timebase::new(1_000)),
+        Duration::from_millis(250)
+    );
+}
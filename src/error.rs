@@ -0,0 +1,101 @@
+use crate::AclintDevice;
+use core::fmt;
+
+/// Errors returned by the checked accessors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClintError {
+    /// `hart` is not less than the number of harts `max`.
+    HartOutOfRange { hart: usize, max: usize },
+}
+
+impl fmt::Display for ClintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HartOutOfRange { hart, max } => {
+                write!(f, "hart {hart} out of range, device has {max} harts")
+            }
+        }
+    }
+}
+
+/// Error returned by the [`Aclint`](crate::Aclint) accessors
+/// when the device needed by the operation was not configured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotConfiguredError(pub AclintDevice);
+
+impl fmt::Display for NotConfiguredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} device not configured", self.0)
+    }
+}
+
+/// Error returned by [`SifiveClint::send_and_wait_ack`](crate::SifiveClint::send_and_wait_ack)
+/// and [`SifiveClint::broadcast_ipi_and_wait`](crate::SifiveClint::broadcast_ipi_and_wait)
+/// when the operation did not complete before its deadline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeoutError;
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out")
+    }
+}
+
+/// Error returned by
+/// [`AclintMtimer::write_mtimecmp_global`](crate::AclintMtimer::write_mtimecmp_global)
+/// when hart `hart` is not covered by this device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotCoveredError {
+    pub hart: usize,
+}
+
+impl fmt::Display for NotCoveredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hart {} not covered by this device", self.hart)
+    }
+}
+
+/// Errors returned by [`SifiveClint::try_from_base_addr`](crate::SifiveClint::try_from_base_addr).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlignmentError {
+    /// The base address is zero.
+    Null,
+    /// `base` is not a multiple of the required alignment `align`.
+    Misaligned { base: usize, align: usize },
+}
+
+impl fmt::Display for AlignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null base address"),
+            Self::Misaligned { base, align } => {
+                write!(f, "base address {base:#x} not aligned to {align:#x}")
+            }
+        }
+    }
+}
+
+/// Errors returned by [`SifiveClint::self_check`](crate::SifiveClint::self_check).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfCheckError {
+    /// `mtimecmp` of hart 0 read back `read` after `wrote` was written.
+    MtimecmpMismatch { wrote: u64, read: u64 },
+    /// `msip` of hart 0 read back `read` after `wrote` was written.
+    MsipMismatch { wrote: bool, read: bool },
+    /// The reserved word of the MSWI read as the given value instead of zero.
+    ReservedNonZero(u32),
+}
+
+impl fmt::Display for SelfCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MtimecmpMismatch { wrote, read } => {
+                write!(f, "mtimecmp read back {read:#x} after writing {wrote:#x}")
+            }
+            Self::MsipMismatch { wrote, read } => {
+                write!(f, "msip read back {read} after writing {wrote}")
+            }
+            Self::ReservedNonZero(value) => write!(f, "reserved word reads {value:#x}"),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{SifiveClint, MTIMER, NEVER};
+
+/// Masks the timer interrupt of a hart while alive, created by [`MTIMER::mask_timer`].
+///
+/// Masking is done through `mtimecmp`, not the `mie.MTIE` CSR:
+/// the guard saves the compare value, writes [`NEVER`], and writes the saved value back on drop.
+/// A deadline that passed meanwhile fires as soon as the guard is dropped.
+#[must_use = "the timer is unmasked again as soon as the guard is dropped"]
+pub struct TimerGuard<'a, const N: usize> {
+    mtimer: &'a MTIMER<N>,
+    hart_idx: usize,
+    saved: u64,
+}
+
+impl<'a, const N: usize> TimerGuard<'a, N> {
+    /// Returns the `mtimecmp` value restored on drop.
+    #[inline]
+    pub fn saved(&self) -> u64 {
+        self.saved
+    }
+}
+
+impl<const N: usize> Drop for TimerGuard<'_, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.mtimer.write_mtimecmp(self.hart_idx, self.saved)
+    }
+}
+
+impl<const N: usize> MTIMER<N> {
+    /// Masks the timer interrupt of `hart_idx` until the returned guard is dropped.
+    #[inline]
+    pub fn mask_timer(&self, hart_idx: usize) -> TimerGuard<'_, N> {
+        TimerGuard {
+            mtimer: self,
+            hart_idx,
+            saved: self.swap_mtimecmp(hart_idx, NEVER),
+        }
+    }
+}
+
+impl<const H: usize> SifiveClint<H> {
+    /// See [`MTIMER::mask_timer`].
+    #[inline]
+    pub fn mask_timer(&self, hart_idx: usize) -> TimerGuard<'_, H> {
+        self.mtimer.mask_timer(hart_idx)
+    }
+}
+
+#[test]
+fn test_timer_guard() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    clint.write_mtimecmp(1, 500);
+    {
+        let guard = clint.mask_timer(1);
+        assert_eq!(guard.saved(), 500);
+        assert_eq!(clint.read_mtimecmp(1), u64::MAX);
+    }
+    assert_eq!(clint.read_mtimecmp(1), 500);
+}
@@ -0,0 +1,74 @@
+use crate::{SifiveClint, Timebase};
+use embedded_hal::{blocking::delay::DelayUs, timer::CountDown};
+use void::Void;
+
+/// Busy-waiting delay and count-down timer on `mtime`, implementing the `embedded-hal` traits.
+///
+/// Durations are measured in microseconds.
+pub struct EmbeddedHalTimer<'a, const H: usize> {
+    clint: &'a SifiveClint<H>,
+    timebase: Timebase,
+    deadline: u64,
+}
+
+impl<'a, const H: usize> EmbeddedHalTimer<'a, H> {
+    /// Creates a timer counting `mtime` at `freq_hz`, which must not be zero.
+    #[inline]
+    pub const fn new(clint: &'a SifiveClint<H>, freq_hz: u64) -> Self {
+        Self {
+            clint,
+            timebase: Timebase::new(freq_hz),
+            deadline: 0,
+        }
+    }
+
+    #[inline]
+    fn deadline_after_us(&self, us: u32) -> u64 {
+        self.timebase
+            .deadline_after(self.clint.read_mtime(), us as u64 * 1000)
+    }
+}
+
+impl<const H: usize> DelayUs<u32> for EmbeddedHalTimer<'_, H> {
+    fn delay_us(&mut self, us: u32) {
+        let deadline = self.deadline_after_us(us);
+        while self.clint.read_mtime() < deadline {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<const H: usize> CountDown for EmbeddedHalTimer<'_, H> {
+    type Time = u32;
+
+    #[inline]
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.deadline = self.deadline_after_us(count.into());
+    }
+
+    #[inline]
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        if self.clint.read_mtime() >= self.deadline {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+#[test]
+fn test_count_down() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    let mut timer = EmbeddedHalTimer::new(&clint, 1_000_000);
+    clint.write_mtime(100);
+    timer.start(20u32);
+    assert!(timer.wait().is_err());
+    clint.write_mtime(119);
+    assert!(timer.wait().is_err());
+    clint.write_mtime(120);
+    assert!(timer.wait().is_ok());
+    timer.delay_us(0);
+}
@@ -0,0 +1,72 @@
+use crate::SifiveClint;
+
+/// The registers of one hart of a CLINT, returned by [`SifiveClint::hart`].
+#[derive(Clone, Copy)]
+pub struct HartClint<'a, const H: usize> {
+    clint: &'a SifiveClint<H>,
+    hart_idx: usize,
+}
+
+impl<const H: usize> SifiveClint<H> {
+    /// Returns a handle operating on `hart_idx`, e.g. the current hart after reading `mhartid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hart_idx` is not less than `H`.
+    #[inline]
+    pub fn hart(&self, hart_idx: usize) -> HartClint<'_, H> {
+        assert!(hart_idx < H, "hart index out of range");
+        HartClint {
+            clint: self,
+            hart_idx,
+        }
+    }
+}
+
+impl<const H: usize> HartClint<'_, H> {
+    #[inline]
+    pub fn hart_idx(&self) -> usize {
+        self.hart_idx
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self) -> u64 {
+        // SAFETY: `hart_idx` was checked by `SifiveClint::hart`.
+        unsafe { self.clint.read_mtimecmp_unchecked(self.hart_idx) }
+    }
+
+    /// Sets the timer deadline, i.e. writes `mtimecmp`.
+    #[inline]
+    pub fn set_timer(&self, val: u64) {
+        unsafe { self.clint.write_mtimecmp_unchecked(self.hart_idx, val) }
+    }
+
+    #[inline]
+    pub fn set_msip(&self) {
+        unsafe { self.clint.set_msip_unchecked(self.hart_idx) }
+    }
+
+    #[inline]
+    pub fn clear_msip(&self) {
+        unsafe { self.clint.clear_msip_unchecked(self.hart_idx) }
+    }
+}
+
+#[test]
+fn test_hart_clint() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    let hart = clint.hart(1);
+    hart.set_timer(30);
+    hart.set_msip();
+    assert_eq!(hart.read_mtimecmp(), 30);
+    assert_eq!(clint.read_mtimecmp(1), 30);
+    assert!(clint.read_msip(1));
+    hart.clear_msip();
+    assert!(!clint.read_msip(1));
+}
+
+#[test]
+#[should_panic]
+fn test_hart_clint_oob() {
+    unsafe { SifiveClint::<2>::new_boxed() }.hart(2);
+}
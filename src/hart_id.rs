@@ -0,0 +1,69 @@
+use crate::SifiveClint;
+
+/// Index of a hart known to be below `H` harts.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HartId<const H: usize>(usize);
+
+impl<const H: usize> HartId<H> {
+    /// Returns `None` if `hart_idx` is not less than `H`.
+    #[inline]
+    pub const fn new(hart_idx: usize) -> Option<Self> {
+        if hart_idx < H {
+            Some(Self(hart_idx))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+impl<const H: usize> From<HartId<H>> for usize {
+    #[inline]
+    fn from(value: HartId<H>) -> Self {
+        value.0
+    }
+}
+
+impl<const H: usize> SifiveClint<H> {
+    #[inline]
+    pub fn read_mtimecmp_by_id(&self, hart: HartId<H>) -> u64 {
+        self.read_mtimecmp(hart.0)
+    }
+
+    #[inline]
+    pub fn write_mtimecmp_by_id(&self, hart: HartId<H>, val: u64) {
+        self.write_mtimecmp(hart.0, val)
+    }
+
+    #[inline]
+    pub fn read_msip_by_id(&self, hart: HartId<H>) -> bool {
+        self.read_msip(hart.0)
+    }
+
+    #[inline]
+    pub fn set_msip_by_id(&self, hart: HartId<H>) {
+        self.set_msip(hart.0)
+    }
+
+    #[inline]
+    pub fn clear_msip_by_id(&self, hart: HartId<H>) {
+        self.clear_msip(hart.0)
+    }
+}
+
+#[test]
+fn test_hart_id() {
+    assert!(HartId::<4>::new(4).is_none());
+    let hart = HartId::<4>::new(3).unwrap();
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    clint.set_msip_by_id(hart);
+    assert!(clint.read_msip(3));
+    clint.write_mtimecmp_by_id(hart, 9);
+    assert_eq!(clint.read_mtimecmp_by_id(hart), 9);
+    assert_eq!(usize::from(hart), 3);
+}
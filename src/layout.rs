@@ -0,0 +1,194 @@
+use crate::{
+    volatile::{ReadLe, WriteLe},
+    NotCoveredError, SifiveClint4095, MAX_HARTS, MTIMECMP,
+};
+use core::mem::size_of;
+
+/// Where the registers of an ACLINT MTIMER device live.
+///
+/// The ACLINT spec allows `mtime` and the `mtimecmp` array to sit in separate regions,
+/// so both bases are absolute addresses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MtimerLayout {
+    /// Address of `mtime`.
+    pub mtime_base: usize,
+    /// Address of `mtimecmp` of hart 0.
+    pub mtimecmp_base: usize,
+    /// Distance in bytes between `mtimecmp`s of adjacent harts,
+    /// `8` on spec-compliant devices and a larger multiple of `8` on padded ones.
+    pub mtimecmp_stride: usize,
+    /// Number of `mtimecmp`s in the device.
+    pub hart_count: usize,
+    /// Global hart ID served by `mtimecmp` 0,
+    /// for platforms whose MTIMERs each cover a sub-range of harts.
+    pub first_hart: usize,
+}
+
+impl MtimerLayout {
+    /// Layout of the MTIMER part of a [`SifiveClint`](crate::SifiveClint) at `base`.
+    #[inline]
+    pub const fn sifive_clint(base: usize) -> Self {
+        Self {
+            mtime_base: base + SifiveClint4095::MTIME_OFFSET,
+            mtimecmp_base: base + SifiveClint4095::MTIMER_OFFSET,
+            mtimecmp_stride: size_of::<MTIMECMP>(),
+            hart_count: MAX_HARTS,
+            first_hart: 0,
+        }
+    }
+
+    /// Translates `global_hart_id` into a device-local index, i.e. subtracts `first_hart`,
+    /// or returns `None` if this device doesn't cover it.
+    #[inline]
+    pub const fn local_index(&self, global_hart_id: usize) -> Option<usize> {
+        match global_hart_id.checked_sub(self.first_hart) {
+            Some(hart_idx) if hart_idx < self.hart_count => Some(hart_idx),
+            _ => None,
+        }
+    }
+
+    /// Returns whether `mtimecmp_stride` is a non-zero multiple of `8`,
+    /// so that every `mtimecmp` is aligned and distinct.
+    #[inline]
+    pub const fn is_stride_valid(&self) -> bool {
+        self.mtimecmp_stride != 0 && self.mtimecmp_stride & (size_of::<MTIMECMP>() - 1) == 0
+    }
+
+    /// Address of `mtimecmp` of `hart_idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hart_idx` is not less than `hart_count`.
+    #[inline]
+    pub const fn mtimecmp_addr(&self, hart_idx: usize) -> usize {
+        assert!(hart_idx < self.hart_count, "hart index out of range");
+        self.mtimecmp_base + hart_idx * self.mtimecmp_stride
+    }
+}
+
+/// An ACLINT MTIMER device whose register addresses are computed from a [`MtimerLayout`].
+pub struct AclintMtimer {
+    layout: MtimerLayout,
+}
+
+impl AclintMtimer {
+    /// Creates an MTIMER accessor over `layout`.
+    ///
+    /// # Safety
+    ///
+    /// Every register address described by `layout` must be a valid, 8-byte aligned
+    /// MTIMER register for the lifetime of the returned value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout.mtimecmp_stride` is not a non-zero multiple of `8`,
+    /// see [`MtimerLayout::is_stride_valid`].
+    #[inline]
+    pub const unsafe fn new(layout: MtimerLayout) -> Self {
+        assert!(
+            layout.is_stride_valid(),
+            "mtimecmp stride must be a non-zero multiple of 8"
+        );
+        Self { layout }
+    }
+
+    #[inline]
+    pub const fn layout(&self) -> &MtimerLayout {
+        &self.layout
+    }
+
+    #[inline]
+    pub fn read_mtime(&self) -> u64 {
+        unsafe { (self.layout.mtime_base as *const u64).read_le() }
+    }
+
+    #[inline]
+    pub fn write_mtime(&self, val: u64) {
+        unsafe { (self.layout.mtime_base as *mut u64).write_le(val) }
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+        unsafe { (self.layout.mtimecmp_addr(hart_idx) as *const u64).read_le() }
+    }
+
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+        unsafe { (self.layout.mtimecmp_addr(hart_idx) as *mut u64).write_le(val) }
+    }
+
+    /// Writes `mtimecmp` of the hart with global ID `global_hart_id`,
+    /// translated by [`MtimerLayout::local_index`].
+    ///
+    /// Fails with [`NotCoveredError`] if the hart belongs to another MTIMER.
+    #[inline]
+    pub fn write_mtimecmp_global(
+        &self,
+        global_hart_id: usize,
+        val: u64,
+    ) -> Result<(), NotCoveredError> {
+        let hart_idx = self
+            .layout
+            .local_index(global_hart_id)
+            .ok_or(NotCoveredError {
+                hart: global_hart_id,
+            })?;
+        self.write_mtimecmp(hart_idx, val);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sifive_layout() {
+    let clint = unsafe { SifiveClint4095::new_boxed() };
+    let base = &*clint as *const SifiveClint4095 as usize;
+    let mtimer = unsafe { AclintMtimer::new(MtimerLayout::sifive_clint(base)) };
+    clint.write_mtime(5);
+    mtimer.write_mtimecmp(7, 9);
+    assert_eq!(mtimer.read_mtime(), 5);
+    assert_eq!(clint.read_mtimecmp(7), 9);
+}
+
+#[test]
+fn test_write_mtimecmp_global() {
+    let clint = unsafe { SifiveClint4095::new_boxed() };
+    let base = &*clint as *const SifiveClint4095 as usize;
+    let layout = MtimerLayout {
+        hart_count: 4,
+        first_hart: 4,
+        ..MtimerLayout::sifive_clint(base)
+    };
+    let mtimer = unsafe { AclintMtimer::new(layout) };
+    assert_eq!(mtimer.write_mtimecmp_global(5, 9), Ok(()));
+    assert_eq!(clint.read_mtimecmp(1), 9);
+    assert_eq!(
+        mtimer.write_mtimecmp_global(3, 0),
+        Err(NotCoveredError { hart: 3 })
+    );
+    assert!(mtimer.write_mtimecmp_global(8, 0).is_err());
+}
+
+#[test]
+fn test_mtimecmp_stride() {
+    let clint = unsafe { SifiveClint4095::new_boxed() };
+    let base = &*clint as *const SifiveClint4095 as usize;
+    let layout = MtimerLayout {
+        mtimecmp_stride: 16,
+        hart_count: 4,
+        ..MtimerLayout::sifive_clint(base)
+    };
+    let mtimer = unsafe { AclintMtimer::new(layout) };
+    mtimer.write_mtimecmp(3, 9);
+    assert_eq!(clint.read_mtimecmp(6), 9);
+
+    assert!(!MtimerLayout {
+        mtimecmp_stride: 12,
+        ..layout
+    }
+    .is_stride_valid());
+    assert!(!MtimerLayout {
+        mtimecmp_stride: 0,
+        ..layout
+    }
+    .is_stride_valid());
+}
@@ -1,8 +1,82 @@
 #![no_std]
-#![feature(naked_functions, asm_const)]
 #![deny(warnings)]
 
-use core::{arch::asm, cell::UnsafeCell, mem::size_of};
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+#[cfg(feature = "fdt")]
+extern crate alloc;
+
+pub mod addresses;
+pub mod sim;
+
+mod aclint;
+mod clint;
+mod clock;
+mod error;
+mod guard;
+mod hart;
+mod hart_id;
+mod layout;
+mod mmio;
+#[cfg(all(
+    feature = "naked",
+    any(target_arch = "riscv32", target_arch = "riscv64")
+))]
+mod naked;
+mod probe;
+mod readonly;
+mod reg;
+mod rv32;
+mod snapshot;
+mod timebase;
+mod view;
+mod volatile;
+
+#[cfg(feature = "embedded-hal")]
+mod hal;
+
+#[cfg(feature = "rustsbi")]
+mod sbi;
+
+#[cfg(feature = "fdt")]
+mod fdt;
+
+pub use aclint::{Aclint, AclintBuilder};
+pub use clint::{Clint, MtimerOps, SoftIntOps};
+pub use clock::{ClintClock, ClintInstant};
+pub use error::{
+    AlignmentError, ClintError, NotConfiguredError, NotCoveredError, SelfCheckError, TimeoutError,
+};
+pub use guard::TimerGuard;
+pub use hart::HartClint;
+pub use hart_id::HartId;
+pub use layout::{AclintMtimer, MtimerLayout};
+pub use mmio::{ClintOver, DirectMmio, MmioAccess};
+pub use probe::{detect_layout_by_mtime, probe, AclintDevice, AclintHandle, ClintLayout};
+pub use readonly::ReadOnlyMtime;
+pub use reg::ClintReg;
+pub use rv32::{Rv32Mtimer, Rv32Mtimer4095, Rv32Reg};
+pub use snapshot::{ClintSnapshot, HartSnapshot};
+pub use timebase::Timebase;
+pub use view::ClintView;
+
+#[cfg(feature = "embedded-hal")]
+pub use hal::EmbeddedHalTimer;
+
+#[cfg(feature = "rustsbi")]
+pub use sbi::{IpiClint, TimerClint};
+
+#[cfg(feature = "fdt")]
+pub use fdt::clint_fdt_node;
+
+use core::{
+    cell::UnsafeCell,
+    mem::{align_of, size_of, ManuallyDrop},
+    ops::{Deref, Range},
+};
+use rv32::{split_mtimecmp_write, Half};
+use volatile::{ReadLe, WriteLe};
 
 #[repr(transparent)]
 pub struct MTIME(UnsafeCell<u64>);
@@ -16,194 +90,2049 @@ pub struct MSIP(UnsafeCell<u32>);
 #[repr(transparent)]
 pub struct SETSSIP(UnsafeCell<u32>);
 
-#[repr(transparent)]
-pub struct MTIMER([MTIMECMP; 4095]);
+// SAFETY: the registers are device memory, accessed only through aligned volatile loads
+// and stores of their native width. Such device accesses are single-copy atomic,
+// so accesses from different harts never race, only the order in which they land is unspecified.
+// This makes `MTIMER`, `MSWI`, `SSWI` and `SifiveClint` `Sync`,
+// so a `&'static SifiveClint` can live in a `static` with no unsafe code downstream.
+// Devices in ordinary memory have no such guarantee, hence the unsafe `new_boxed`.
+unsafe impl Sync for MTIME {}
+unsafe impl Sync for MTIMECMP {}
+unsafe impl Sync for MSIP {}
+unsafe impl Sync for SETSSIP {}
 
-#[repr(transparent)]
-pub struct MSWI([MSIP; 4095]);
+/// `mtimecmp` registers padded to the `0x7ff8` bytes before `mtime`,
+/// so that `mtime` stays where the hardware has it whatever the hart count.
+#[repr(C)]
+pub union MtimecmpArray<T, const N: usize> {
+    regs: ManuallyDrop<[T; N]>,
+    _pad: [u64; MAX_HARTS],
+}
 
-#[repr(transparent)]
-pub struct SSWI([SETSSIP; 4095]);
+impl<T, const N: usize> Deref for MtimecmpArray<T, N> {
+    type Target = [T; N];
 
+    #[inline]
+    fn deref(&self) -> &[T; N] {
+        const { assert_capacity(N) };
+        // SAFETY: `regs` covers the start of the union and is the only field ever read.
+        unsafe { &self.regs }
+    }
+}
+
+#[repr(C)]
+pub struct MTIMER<const N: usize> {
+    pub mtimecmp: MtimecmpArray<MTIMECMP, N>,
+    pub mtime: MTIME,
+}
+
+/// The alignment pads the device to its 16 KiB window, covering the reserved word after `msip`s.
+#[repr(C, align(0x4000))]
+pub struct MSWI<const N: usize>([MSIP; N]);
+
+/// The alignment pads the device to its 16 KiB window, covering the reserved word after `setssip`s.
+#[repr(C, align(0x4000))]
+pub struct SSWI<const N: usize>([SETSSIP; N]);
+
+/// A SiFive CLINT with `H` harts: MSWI followed by MTIMER.
+///
+/// `mtime` sits at the canonical offset `0xbff8` whatever `H` is,
+/// leaving a gap after the implemented `mtimecmp`s as on real chips with fewer harts,
+/// e.g. [`Fu540Clint`], [`Fu740Clint`] and [`QemuVirtClint`],
+/// so one type serves every SiFive-compatible CLINT and no separate fixed-`mtime` layout is needed.
 #[repr(C)]
-pub struct SifiveClint {
-    mswi: MSWI,
-    reserve: u32,
-    mtimer: MTIMER,
-    mtime: MTIME,
+pub struct SifiveClint<const H: usize> {
+    mswi: MSWI<H>,
+    mtimer: MTIMER<H>,
+}
+
+/// Maximum number of harts an ACLINT device or SiFive CLINT can serve.
+pub const MAX_HARTS: usize = 4095;
+
+/// Fails the build of any device type with more than [`MAX_HARTS`] harts,
+/// whose layout would otherwise silently move `mtime`, when evaluated in a `const` context.
+pub(crate) const fn assert_capacity(n: usize) {
+    assert!(n <= MAX_HARTS, "a device serves at most MAX_HARTS harts");
+}
+
+/// Panics with a message naming the hart and the capacity if `hart_idx` is out of range,
+/// when the `strict-bounds` feature is enabled.
+///
+/// Without the feature the indexing accessors still panic through the slice bounds check,
+/// only with the generic message.
+#[inline(always)]
+pub(crate) fn check_hart<const N: usize>(hart_idx: usize) {
+    const { assert_capacity(N) };
+    #[cfg(feature = "strict-bounds")]
+    assert!(
+        hart_idx < N,
+        "hart {hart_idx} out of range, device has {N} harts"
+    );
+    #[cfg(not(feature = "strict-bounds"))]
+    let _ = hart_idx;
+}
+
+/// Interrupt code of the machine timer interrupt, raised by MTIMER when `mtime >= mtimecmp`.
+pub const M_TIMER_INT: usize = 7;
+/// Interrupt code of the machine software interrupt, raised by MSWI through `msip`.
+pub const M_SOFT_INT: usize = 3;
+/// Interrupt code of the supervisor software interrupt, raised by SSWI through `setssip`.
+pub const S_SOFT_INT: usize = 1;
+
+/// The `mtimecmp` value that `mtime` never exceeds, so writing it disables the timer interrupt.
+pub const NEVER: u64 = u64::MAX;
+
+/// Returns the ticks from `earlier` to `later`, two samples of `mtime`.
+///
+/// The subtraction wraps, so the interval stays correct across a counter wrap.
+/// That is mostly theoretical: a 64-bit counter wraps after about 584 years at 1 GHz.
+///
+/// This is a free function rather than an associated one of [`MTIMER`],
+/// which would have to be called with the hart count spelled out, as `MTIMER::<N>::elapsed_ticks`,
+/// although the result does not depend on it.
+#[inline]
+pub const fn elapsed_ticks(earlier: u64, later: u64) -> u64 {
+    later.wrapping_sub(earlier)
+}
+
+/// Reads the `time` CSR, which mirrors `mtime`, on RV32 as `timeh`, `time`, `timeh`
+/// retrying if the high half changed in between.
+///
+/// Supervisor and user mode code should use this: the CLINT is usually mapped for
+/// machine mode only, while `time` is readable wherever `mcounteren.TM`
+/// (and `scounteren.TM` for user mode) allows, or emulated by the SBI implementation.
+/// Machine mode code on platforms that do not implement `time` must read `mtime`
+/// through MMIO with [`SifiveClint::read_mtime`] or [`MTIMER::read_mtime`] instead.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+pub fn read_time_csr() -> u64 {
+    #[cfg(target_arch = "riscv64")]
+    {
+        let ans: u64;
+        unsafe { core::arch::asm!("csrr {}, time", out(reg) ans, options(nomem, nostack)) };
+        ans
+    }
+    #[cfg(target_arch = "riscv32")]
+    loop {
+        let (hi, lo, hi2): (u32, u32, u32);
+        unsafe {
+            core::arch::asm!(
+                "csrr {hi}, timeh",
+                "csrr {lo}, time",
+                "csrr {hi2}, timeh",
+                hi = out(reg) hi,
+                lo = out(reg) lo,
+                hi2 = out(reg) hi2,
+                options(nomem, nostack),
+            )
+        };
+        if hi == hi2 {
+            break ((hi as u64) << 32) | lo as u64;
+        }
+    }
 }
 
-impl SifiveClint {
-    const MTIMER_OFFSET: usize = size_of::<MSWI>() + size_of::<u32>();
-    const MTIME_OFFSET: usize = Self::MTIMER_OFFSET + size_of::<MTIMER>();
+pub type MTIMER4095 = MTIMER<MAX_HARTS>;
+pub type MSWI4095 = MSWI<MAX_HARTS>;
+pub type SSWI4095 = SSWI<MAX_HARTS>;
+pub type SifiveClint4095 = SifiveClint<MAX_HARTS>;
+
+/// CLINT sized for any spec-conformant platform.
+pub type SpecMaxClint = SifiveClint<MAX_HARTS>;
+/// CLINT of QEMU `virt` with up to 8 harts per socket, see [`addresses::QEMU_VIRT_CLINT`].
+///
+/// Machines started with more harts per socket need [`SpecMaxClint`].
+pub type QemuVirtClint = SifiveClint<8>;
+/// CLINT of SiFive FU540-C000, serving the E51 monitor core and 4 U54 cores.
+pub type Fu540Clint = SifiveClint<5>;
+/// CLINT of SiFive FU740-C000, serving the S7 monitor core and 4 U74 cores.
+pub type Fu740Clint = SifiveClint<5>;
+
+// The layouts must match the hardware register maps exactly.
+const _: () = assert!(size_of::<MSWI4095>() == 0x4000);
+const _: () = assert!(size_of::<SSWI4095>() == 0x4000);
+const _: () = assert!(size_of::<MTIMER4095>() == 0x8000);
+const _: () = assert!(size_of::<SifiveClint4095>() == 0xc000);
+const _: () = assert!(size_of::<Fu540Clint>() == 0xc000);
+const _: () = assert!(Fu540Clint::mtime_offset() == 0xbff8);
+const _: () = assert!(QemuVirtClint::mtime_offset() == 0xbff8);
+const _: () = assert!(SifiveClint::<1>::mtime_offset() == 0xbff8);
+// The word after the last hart is reserved, see the register maps at
+// <https://github.com/riscv/riscv-aclint/blob/main/riscv-aclint.adoc>.
+const _: () = assert!(MSWI4095::msip_offset(4095) == MSWI4095::RESERVED_OFFSET);
+const _: () = assert!(SSWI4095::setssip_offset(4095) == SSWI4095::RESERVED_OFFSET);
+
+impl<const N: usize> MTIMER<N> {
+    /// Byte offset of `mtime` from the MTIMER base.
+    pub const MTIME_OFFSET: usize = {
+        assert_capacity(N);
+        size_of::<MtimecmpArray<MTIMECMP, N>>()
+    };
+
+    /// Byte offset of `mtimecmp` of `hart_idx` from the MTIMER base.
+    #[inline]
+    pub const fn mtimecmp_offset(hart_idx: usize) -> usize {
+        const { assert_capacity(N) };
+        hart_idx * size_of::<MTIMECMP>()
+    }
+
+    /// Byte offset of `mtime` from the MTIMER base.
+    #[inline]
+    pub const fn mtime_offset() -> usize {
+        Self::MTIME_OFFSET
+    }
+
+    /// Length in bytes of the MTIMER register window.
+    #[inline]
+    pub const fn region_len() -> usize {
+        const { assert_capacity(N) };
+        size_of::<Self>()
+    }
+
+    /// Returns a reference to the MTIMER mapped at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be 8-byte aligned and the whole `size_of::<Self>()`-byte region starting at it
+    /// must be a valid MTIMER MMIO window for the rest of the program.
+    #[inline]
+    pub const unsafe fn from_base_addr(base: usize) -> &'static Self {
+        &*(base as *const Self)
+    }
 
     #[inline]
     pub fn read_mtime(&self) -> u64 {
-        unsafe { self.mtime.0.get().read_volatile() }
+        unsafe { self.mtime.0.get().read_le() }
+    }
+
+    /// Reads `mtime` with exactly one `ld` emitted by inline assembly.
+    ///
+    /// Optimized builds usually compile [`read_mtime`](Self::read_mtime) to the same load,
+    /// but this one stays a single inlined instruction at every optimization level,
+    /// and tells the compiler it neither touches the stack nor writes memory,
+    /// so surrounding code can be scheduled around it freely, e.g. in tight polling loops.
+    /// Unlike the `naked` variant it needs no call and keeps the Rust ABI.
+    #[cfg(target_arch = "riscv64")]
+    #[inline(always)]
+    pub fn read_mtime_asm(&self) -> u64 {
+        let ans: u64;
+        unsafe {
+            core::arch::asm!(
+                "ld {ans}, 0({ptr})",
+                ans = lateout(reg) ans,
+                ptr = in(reg) self.mtime.0.get(),
+                options(nostack, readonly, preserves_flags),
+            )
+        };
+        u64::from_le(ans)
+    }
+
+    /// Reads `mtime` between two [`compiler_fence`](core::sync::atomic::compiler_fence)s,
+    /// so that the compiler does not move surrounding memory accesses across the read,
+    /// e.g. when timing a region of code.
+    ///
+    /// This is a compiler fence only, no `fence` instruction is issued,
+    /// so the hart itself may still reorder the accesses.
+    #[inline]
+    pub fn read_mtime_fenced(&self) -> u64 {
+        use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
+        compiler_fence(SeqCst);
+        let ans = self.read_mtime();
+        compiler_fence(SeqCst);
+        ans
+    }
+
+    /// Reads `mtime` twice and returns the larger value.
+    ///
+    /// Guards against hardware or simulators whose consecutive reads can go backwards,
+    /// keeping the values seen by one caller non-decreasing.
+    /// On well-behaved hardware this is the same as [`read_mtime`](Self::read_mtime),
+    /// at the cost of a second read.
+    #[inline]
+    pub fn read_mtime_monotonic(&self) -> u64 {
+        let a = self.read_mtime();
+        let b = self.read_mtime();
+        a.max(b)
+    }
+
+    /// Reads `mtime` `iters + 1` times back to back and returns the smallest difference
+    /// between consecutive reads, i.e. the cost of one read in ticks,
+    /// to subtract from measurements taken with two reads.
+    ///
+    /// Results vary with pipeline and cache state, so calibrate with enough iterations
+    /// on the hart that will take the measurements. On harts much faster than the timebase
+    /// this is usually `0`, meaning a read costs less than a tick.
+    /// Returns `0` if `iters` is `0`.
+    pub fn measure_read_overhead(&self, iters: usize) -> u64 {
+        let mut min = if iters == 0 { 0 } else { u64::MAX };
+        let mut prev = self.read_mtime();
+        for _ in 0..iters {
+            let now = self.read_mtime();
+            min = min.min(now.wrapping_sub(prev));
+            prev = now;
+        }
+        min
+    }
+
+    /// Reads `mtime` as two 32-bit halves, retrying if the high half changed in between.
+    ///
+    /// RV32 platforms should prefer this over [`read_mtime`](Self::read_mtime),
+    /// which may tear when the low half rolls over. RV64 platforms should use `read_mtime`.
+    #[inline]
+    pub fn read_mtime_rv32(&self) -> u64 {
+        loop {
+            let hi = self.read_mtime_hi();
+            let lo = self.read_mtime_lo();
+            if hi == self.read_mtime_hi() {
+                break ((hi as u64) << 32) | lo as u64;
+            }
+        }
+    }
+
+    /// Reads the low 32 bits of `mtime`.
+    #[inline]
+    pub fn read_mtime_lo(&self) -> u32 {
+        unsafe { self.mtime.0.get().cast::<u32>().read_le() }
+    }
+
+    /// Reads the high 32 bits of `mtime`.
+    #[inline]
+    pub fn read_mtime_hi(&self) -> u32 {
+        unsafe { self.mtime.0.get().cast::<u32>().add(1).read_le() }
     }
 
     #[inline]
     pub fn write_mtime(&self, val: u64) {
-        unsafe { self.mtime.0.get().write_volatile(val) }
+        unsafe { self.mtime.0.get().write_le(val) }
+    }
+
+    /// Resets `mtime` to `0` on cold boot.
+    ///
+    /// Call exactly once from the boot hart before enabling timers:
+    /// `mtime` is shared by all harts, and on some platforms it is undefined until written.
+    /// Calling it after other harts have programmed `mtimecmp` moves their deadlines
+    /// far into the future, or fires no interrupt at all until `mtime` catches up again.
+    #[inline]
+    pub fn init_mtime(&self) {
+        self.write_mtime(0)
+    }
+
+    /// Reads `mtime` and formats it as decimal ticks into `buf`, without allocating.
+    #[inline]
+    pub fn fmt_mtime<'b>(&self, buf: &'b mut [u8; 20]) -> &'b str {
+        fmt_decimal(self.read_mtime(), buf)
+    }
+
+    /// Captures `mtime` so that [`thaw_mtime`](Self::thaw_mtime) can restore it later.
+    ///
+    /// The hardware keeps counting; this only approximates a stopped clock, mainly for tests.
+    #[inline]
+    pub fn freeze_mtime(&self) -> u64 {
+        self.read_mtime()
+    }
+
+    /// Writes back a value captured by [`freeze_mtime`](Self::freeze_mtime).
+    #[inline]
+    pub fn thaw_mtime(&self, frozen: u64) {
+        self.write_mtime(frozen)
+    }
+
+    /// Runs `f` and then restores `mtime` to its value before `f`, hiding the elapsed time.
+    ///
+    /// The hardware keeps counting; this only approximates a stopped clock, mainly for tests.
+    #[inline]
+    pub fn with_frozen_mtime<R>(&self, f: impl FnOnce() -> R) -> R {
+        let frozen = self.freeze_mtime();
+        let ans = f();
+        self.thaw_mtime(frozen);
+        ans
+    }
+
+    /// Writes the low 32 bits of `mtime`.
+    ///
+    /// Writing the halves separately is only safe while the counter is stopped,
+    /// or if the platform tolerates the transient combined value.
+    #[inline]
+    pub fn write_mtime_lo(&self, val: u32) {
+        unsafe { self.mtime.0.get().cast::<u32>().write_le(val) }
+    }
+
+    /// Writes the high 32 bits of `mtime`.
+    ///
+    /// Writing the halves separately is only safe while the counter is stopped,
+    /// or if the platform tolerates the transient combined value.
+    #[inline]
+    pub fn write_mtime_hi(&self, val: u32) {
+        unsafe { self.mtime.0.get().cast::<u32>().add(1).write_le(val) }
+    }
+
+    /// Writes `mtimecmp` of `hart_idx` as three 32-bit stores: high `u32::MAX`, low, then high.
+    ///
+    /// On RV32 [`write_mtimecmp`](Self::write_mtimecmp) is compiled to two 32-bit stores
+    /// in no particular order, which may leave a compare value in the past for a moment
+    /// and fire a spurious timer interrupt. Writing the high half to `u32::MAX` first
+    /// keeps every intermediate compare value in the future.
+    #[inline]
+    pub fn write_mtimecmp_rv32_safe(&self, hart_idx: usize, val: u64) {
+        check_hart::<N>(hart_idx);
+        let lo = self.mtimecmp[hart_idx].0.get().cast::<u32>();
+        for (half, word) in split_mtimecmp_write(val) {
+            let ptr = match half {
+                Half::Lo => lo,
+                Half::Hi => unsafe { lo.add(1) },
+            };
+            unsafe { ptr.write_le(word) };
+        }
     }
 
     #[inline]
     pub fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
-        unsafe { self.mtimer.0[hart_idx].0.get().read_volatile() }
+        check_hart::<N>(hart_idx);
+        unsafe { self.mtimecmp[hart_idx].0.get().read_le() }
     }
 
     #[inline]
     pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
-        unsafe { self.mtimer.0[hart_idx].0.get().write_volatile(val) }
+        check_hart::<N>(hart_idx);
+        unsafe { self.mtimecmp[hart_idx].0.get().write_le(val) }
     }
 
     #[inline]
-    pub fn read_msip(&self, hart_idx: usize) -> bool {
-        unsafe { self.mswi.0[hart_idx].0.get().read_volatile() != 0 }
+    pub fn mtime_ptr(&self) -> *const u64 {
+        self.mtime.0.get()
     }
 
     #[inline]
-    pub fn set_msip(&self, hart_idx: usize) {
-        unsafe { self.mswi.0[hart_idx].0.get().write_volatile(1) }
+    pub fn mtime_mut_ptr(&self) -> *mut u64 {
+        self.mtime.0.get()
     }
 
     #[inline]
-    pub fn clear_msip(&self, hart_idx: usize) {
-        unsafe { self.mswi.0[hart_idx].0.get().write_volatile(0) }
+    pub fn mtimecmp_ptr(&self, hart_idx: usize) -> *const u64 {
+        check_hart::<N>(hart_idx);
+        self.mtimecmp[hart_idx].0.get()
     }
-}
 
-impl SifiveClint {
-    #[naked]
-    pub extern "C" fn read_mtime_naked(&self) -> u64 {
-        unsafe {
-            asm!(
-                "   addi sp, sp, -8
-                    sd   a1, (sp)
+    #[inline]
+    pub fn mtimecmp_mut_ptr(&self, hart_idx: usize) -> *mut u64 {
+        check_hart::<N>(hart_idx);
+        self.mtimecmp[hart_idx].0.get()
+    }
 
-                    li   a1, {offset}
-                    add  a0, a0, a1
+    /// Disables the timer interrupt of `hart_idx` by writing [`NEVER`] to its `mtimecmp`,
+    /// so that `mtime` never reaches the compare value.
+    #[inline]
+    pub fn clear_mtimecmp(&self, hart_idx: usize) {
+        self.write_mtimecmp(hart_idx, NEVER)
+    }
 
-                    ld   a1, (sp)
-                    addi sp, sp,  8
+    /// Writes `new` to `mtimecmp` of `hart_idx` and returns the value it replaced.
+    ///
+    /// This is a plain volatile read followed by a volatile write, not an atomic swap.
+    /// That's enough because only software writes `mtimecmp`,
+    /// but concurrent writers on other harts still have to be serialized by the caller.
+    #[inline]
+    pub fn swap_mtimecmp(&self, hart_idx: usize, new: u64) -> u64 {
+        let old = self.read_mtimecmp(hart_idx);
+        self.write_mtimecmp(hart_idx, new);
+        old
+    }
 
-                    ld   a0, (a0)
-                    ret
-                ",
-                offset = const Self::MTIME_OFFSET,
-                options(noreturn),
-            )
-        }
+    /// Sets `mtimecmp` of `hart_idx` to `delta` ticks after the current `mtime`.
+    ///
+    /// Same as [`set_mtimecmp_after_saturating`](Self::set_mtimecmp_after_saturating),
+    /// so the deadline never lands in the past.
+    /// `mtime` keeps counting between the read and the write, so the deadline may be slightly late.
+    #[inline]
+    pub fn set_mtimecmp_after(&self, hart_idx: usize, delta: u64) {
+        self.set_mtimecmp_after_saturating(hart_idx, delta)
     }
 
-    #[naked]
-    pub extern "C" fn write_mtime_naked(&self, val: u64) -> u64 {
-        unsafe {
-            asm!(
-                "   addi sp, sp, -8
-                    sd   a1, (sp)
+    /// Sets `mtimecmp` of `hart_idx` to `mtime + delta`, saturating at `u64::MAX`.
+    ///
+    /// With `mtime == u64::MAX - 1` and `delta == 2` the deadline is pinned to `u64::MAX`,
+    /// which effectively disables the timer interrupt.
+    #[inline]
+    pub fn set_mtimecmp_after_saturating(&self, hart_idx: usize, delta: u64) {
+        self.write_mtimecmp(hart_idx, self.read_mtime().saturating_add(delta))
+    }
 
-                    li   a1, {offset}
-                    add  a0, a0, a1
+    /// Sets `mtimecmp` of `hart_idx` to `mtime + delta`, wrapping around like the hardware counter.
+    ///
+    /// With `mtime == u64::MAX - 1` and `delta == 2` the deadline wraps to `0`,
+    /// which is already in the past and fires at once.
+    #[inline]
+    pub fn set_mtimecmp_after_wrapping(&self, hart_idx: usize, delta: u64) {
+        self.write_mtimecmp(hart_idx, self.read_mtime().wrapping_add(delta))
+    }
 
-                    ld   a1, (sp)
-                    addi sp, sp,  8
+    /// Moves the deadline of `hart_idx` `period` ticks past its current `mtimecmp`, wrapping.
+    ///
+    /// Unlike [`set_mtimecmp_after`](Self::set_mtimecmp_after), which counts from the current
+    /// `mtime` and so drifts by the interrupt latency on every tick,
+    /// successive deadlines form an exact arithmetic sequence.
+    /// This is the primitive for a fixed-frequency OS tick.
+    #[inline]
+    pub fn advance_mtimecmp(&self, hart_idx: usize, period: u64) {
+        self.write_mtimecmp(hart_idx, self.read_mtimecmp(hart_idx).wrapping_add(period))
+    }
 
-                    sd   a1, (a0)
-                    ret
-                ",
-                offset = const Self::MTIME_OFFSET,
-                options(noreturn),
-            )
+    /// Spins until `mtime` reaches `deadline`, hinting the core with
+    /// [`spin_loop`](core::hint::spin_loop) on every iteration.
+    ///
+    /// Returns at once if `deadline` is already behind `mtime`.
+    #[inline]
+    pub fn busy_wait_until(&self, deadline: u64) {
+        while self.read_mtime() < deadline {
+            core::hint::spin_loop();
         }
     }
 
-    #[naked]
-    pub extern "C" fn read_mtimecmp_naked(&self, hart_idx: usize) -> u64 {
-        unsafe {
-            asm!(
-                "   slli a1, a1, 3
-                    add  a0, a0, a1
-
-                    li   a1, {offset}
-                    add  a0, a0, a1
-
-                    ld   a0, (a0)
-                    ret
-                ",
-                offset = const Self::MTIMER_OFFSET,
-                options(noreturn),
+    /// Spins for `ticks` ticks of `mtime`, see [`busy_wait_until`](Self::busy_wait_until).
+    ///
+    /// The deadline saturates at `u64::MAX` instead of wrapping into the past.
+    #[inline]
+    pub fn busy_wait_ticks(&self, ticks: u64) {
+        self.busy_wait_until(self.read_mtime().saturating_add(ticks))
+    }
+
+    /// Atomically replaces `mtimecmp` of `hart_idx` with `new` if it equals `current`,
+    /// returning the previous value like [`AtomicU64::compare_exchange`](core::sync::atomic::AtomicU64::compare_exchange).
+    ///
+    /// This issues AMO/LR-SC instructions on the MMIO region.
+    /// Platforms whose PMA does not allow atomics on the CLINT will raise an access fault,
+    /// and callers there have to serialize updates with a lock instead.
+    ///
+    /// Only available with `cfg(target_has_atomic = "64")`, so not on RV32.
+    /// There, serialize read-modify-write updates with a lock around
+    /// [`read_mtimecmp`](Self::read_mtimecmp) and
+    /// [`write_mtimecmp_rv32_safe`](Self::write_mtimecmp_rv32_safe),
+    /// or use [`Rv32Mtimer`] for the split 32-bit registers.
+    #[cfg(target_has_atomic = "64")]
+    #[inline]
+    pub fn compare_exchange_mtimecmp(
+        &self,
+        hart_idx: usize,
+        current: u64,
+        new: u64,
+    ) -> Result<u64, u64> {
+        use core::sync::atomic::{AtomicU64, Ordering};
+        check_hart::<N>(hart_idx);
+        // SAFETY: `AtomicU64` has the same layout as `u64` and the register is 8-byte aligned.
+        let atomic = unsafe { &*self.mtimecmp[hart_idx].0.get().cast::<AtomicU64>() };
+        atomic
+            .compare_exchange(
+                current.to_le(),
+                new.to_le(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
             )
+            .map(u64::from_le)
+            .map_err(u64::from_le)
+    }
+
+    /// Like [`read_mtimecmp`](Self::read_mtimecmp), but fails with [`ClintError::HartOutOfRange`] if `hart_idx` is out of range.
+    #[inline]
+    pub fn try_read_mtimecmp(&self, hart_idx: usize) -> Result<u64, ClintError> {
+        let reg = self
+            .mtimecmp
+            .get(hart_idx)
+            .ok_or(ClintError::HartOutOfRange {
+                hart: hart_idx,
+                max: N,
+            })?;
+        Ok(unsafe { reg.0.get().read_le() })
+    }
+
+    /// Like [`write_mtimecmp`](Self::write_mtimecmp), but fails with [`ClintError::HartOutOfRange`] if `hart_idx` is out of range.
+    #[inline]
+    pub fn try_write_mtimecmp(&self, hart_idx: usize, val: u64) -> Result<(), ClintError> {
+        let reg = self
+            .mtimecmp
+            .get(hart_idx)
+            .ok_or(ClintError::HartOutOfRange {
+                hart: hart_idx,
+                max: N,
+            })?;
+        unsafe { reg.0.get().write_le(val) };
+        Ok(())
+    }
+
+    /// Like [`read_mtimecmp`](Self::read_mtimecmp), but without the bounds check and its panic path.
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than the device capacity `N`, otherwise this is undefined behavior.
+    #[inline]
+    pub unsafe fn read_mtimecmp_unchecked(&self, hart_idx: usize) -> u64 {
+        self.mtimecmp.get_unchecked(hart_idx).0.get().read_le()
+    }
+
+    /// Like [`write_mtimecmp`](Self::write_mtimecmp), but without the bounds check and its panic path.
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than the device capacity `N`, otherwise this is undefined behavior.
+    #[inline]
+    pub unsafe fn write_mtimecmp_unchecked(&self, hart_idx: usize, val: u64) {
+        self.mtimecmp.get_unchecked(hart_idx).0.get().write_le(val)
+    }
+
+    /// Writes `val` to `mtimecmp` of every hart in `harts`, e.g. for a synchronized tick.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `harts.end` exceeds the device capacity `N`, which is at most [`MAX_HARTS`].
+    pub fn write_mtimecmp_range(&self, harts: Range<usize>, val: u64) {
+        assert!(harts.end <= N, "hart range {harts:?} exceeds {N} harts");
+        for reg in &self.mtimecmp[harts] {
+            unsafe { reg.0.get().write_le(val) }
         }
     }
 
-    #[naked]
-    pub extern "C" fn write_mtimecmp_naked(&self, hart_idx: usize, val: u64) {
-        unsafe {
-            asm!(
-                "   slli a1, a1, 3
-                    add  a0, a0, a1
-
-                    li   a1, {offset}
-                    add  a0, a0, a1
-
-                    sd   a2, (a0)
-                    ret
-                ",
-                offset = const Self::MTIMER_OFFSET,
-                options(noreturn),
-            )
+    /// Writes [`NEVER`] to `mtimecmp` of harts `0..hart_count`, so no timer fires
+    /// before each hart programs its own deadline, a usual boot-time step.
+    ///
+    /// Harts beyond `hart_count` are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hart_count` exceeds the device capacity `N`.
+    #[inline]
+    pub fn disable_all_timers(&self, hart_count: usize) {
+        self.write_mtimecmp_range(0..hart_count, NEVER)
+    }
+
+    /// Lazily reads `mtimecmp` of each hart in `harts`, clipped to the device capacity `N`.
+    ///
+    /// Every element is a separate volatile read, performed only when the iterator reaches it.
+    pub fn mtimecmp_iter(&self, harts: Range<usize>) -> impl Iterator<Item = u64> + '_ {
+        (harts.start..harts.end.min(N)).map(move |i| self.read_mtimecmp(i))
+    }
+
+    /// Returns whether the deadline of `hart_idx` has passed, i.e. `mtimecmp <= mtime`,
+    /// in which case enabling `mie.MTIE` traps at once.
+    ///
+    /// Both registers are read once and the answer may be stale right away,
+    /// since `mtime` keeps counting and other harts may reprogram `mtimecmp`.
+    #[inline]
+    pub fn is_timer_expired(&self, hart_idx: usize) -> bool {
+        self.read_mtimecmp(hart_idx) <= self.read_mtime()
+    }
+
+    /// Returns the ticks left until the deadline of `hart_idx`,
+    /// or `None` if it has passed, matching [`is_timer_expired`](Self::is_timer_expired).
+    ///
+    /// `mtime` is read before `mtimecmp`, so the result may overstate the remaining ticks
+    /// by the few that elapse between the two reads, and is stale once returned.
+    #[inline]
+    pub fn ticks_until_deadline(&self, hart_idx: usize) -> Option<u64> {
+        let mtime = self.read_mtime();
+        let mtimecmp = self.read_mtimecmp(hart_idx);
+        (mtimecmp > mtime).then(|| mtimecmp - mtime)
+    }
+
+    /// Returns harts in `harts` whose `mtimecmp` is less than or equal to `mtime`.
+    ///
+    /// `mtime` is read once up front, so all harts are compared against the same instant.
+    /// The result is a snapshot and may be stale as soon as it is returned.
+    /// Harts beyond the device capacity `N` are skipped.
+    pub fn expired_timers(&self, harts: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        let now = self.read_mtime();
+        (harts.start..harts.end.min(N)).filter(move |&i| self.read_mtimecmp(i) <= now)
+    }
+}
+
+impl<const N: usize> MSWI<N> {
+    /// Byte offset of the reserved word at the end of the MSWI window.
+    pub const RESERVED_OFFSET: usize = 0x3ffc;
+
+    /// Length in bytes of the MSWI register window.
+    #[inline]
+    pub const fn region_len() -> usize {
+        const { assert_capacity(N) };
+        size_of::<Self>()
+    }
+
+    /// Byte offset of `msip` of `hart_idx` from the MSWI base.
+    #[inline]
+    pub const fn msip_offset(hart_idx: usize) -> usize {
+        const { assert_capacity(N) };
+        hart_idx * size_of::<MSIP>()
+    }
+
+    /// Reads the reserved word at [`RESERVED_OFFSET`](Self::RESERVED_OFFSET).
+    ///
+    /// Diagnostic only, for checking the address decode during bring-up:
+    /// hardware usually reads this word as zero, so reading something that looks like
+    /// a `mtimecmp` value instead hints at a wrong base address.
+    /// It is available in release builds too, since [`SifiveClint::self_check`] relies on it.
+    #[inline]
+    pub fn read_reserved(&self) -> u32 {
+        // SAFETY: the alignment pads `Self` to its 16 KiB window.
+        unsafe { read_reserved_word(self) }
+    }
+
+    /// Returns a reference to the MSWI mapped at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be 16 KiB aligned and the whole `size_of::<Self>()`-byte region starting at it
+    /// must be a valid MSWI MMIO window for the rest of the program.
+    #[inline]
+    pub const unsafe fn from_base_addr(base: usize) -> &'static Self {
+        &*(base as *const Self)
+    }
+
+    #[inline]
+    pub fn read_msip(&self, hart_idx: usize) -> bool {
+        self.read_msip_raw(hart_idx) != 0
+    }
+
+    /// Reads the whole 32-bit `msip` word of `hart_idx`.
+    ///
+    /// Only bit 0 is defined by the spec; other bits set usually indicate an address decode error.
+    #[inline]
+    pub fn read_msip_raw(&self, hart_idx: usize) -> u32 {
+        check_hart::<N>(hart_idx);
+        unsafe { self.0[hart_idx].0.get().read_le() }
+    }
+
+    /// Writes the whole 32-bit `msip` word of `hart_idx`, e.g. to replay a trapped guest access.
+    ///
+    /// On real hardware only bit 0 is meaningful; firmware should use
+    /// [`set_msip`](Self::set_msip) and [`clear_msip`](Self::clear_msip) instead.
+    #[inline]
+    pub fn write_msip_raw(&self, hart_idx: usize, val: u32) {
+        check_hart::<N>(hart_idx);
+        unsafe { self.0[hart_idx].0.get().write_le(val) }
+    }
+
+    #[inline]
+    pub fn set_msip(&self, hart_idx: usize) {
+        check_hart::<N>(hart_idx);
+        unsafe { self.0[hart_idx].0.get().write_le(1) }
+    }
+
+    #[inline]
+    pub fn clear_msip(&self, hart_idx: usize) {
+        check_hart::<N>(hart_idx);
+        unsafe { self.0[hart_idx].0.get().write_le(0) }
+    }
+
+    /// Like [`set_msip`](Self::set_msip), but without the bounds check and its panic path.
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than the device capacity `N`, otherwise this is undefined behavior.
+    #[inline]
+    pub unsafe fn set_msip_unchecked(&self, hart_idx: usize) {
+        self.0.get_unchecked(hart_idx).0.get().write_le(1)
+    }
+
+    /// Like [`clear_msip`](Self::clear_msip), but without the bounds check and its panic path.
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than the device capacity `N`, otherwise this is undefined behavior.
+    #[inline]
+    pub unsafe fn clear_msip_unchecked(&self, hart_idx: usize) {
+        self.0.get_unchecked(hart_idx).0.get().write_le(0)
+    }
+
+    /// Reads `msip` of the current hart, indexed by its `mhartid`.
+    ///
+    /// Assumes hart index 0 of the device is the hart with `mhartid` 0,
+    /// which does not hold for devices covering a sub-range of harts. Machine mode only.
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    #[inline]
+    pub fn read_own_msip(&self) -> bool {
+        self.read_msip(mhartid())
+    }
+
+    /// Clears `msip` of the current hart, see [`read_own_msip`](Self::read_own_msip).
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    #[inline]
+    pub fn clear_own_msip(&self) {
+        self.clear_msip(mhartid())
+    }
+
+    /// Clears `msip` of `hart_idx` if it is set, returning whether it was.
+    ///
+    /// Clearing before handling the IPI means a sender re-signalling meanwhile
+    /// sets `msip` again instead of being lost.
+    #[inline]
+    pub fn take_msip(&self, hart_idx: usize) -> bool {
+        let pending = self.read_msip(hart_idx);
+        if pending {
+            self.clear_msip(hart_idx);
         }
+        pending
     }
 
-    #[naked]
-    pub extern "C" fn read_msip_naked(&self, hart_idx: usize) -> bool {
-        unsafe {
-            asm!(
-                "   slli a1, a1, 2
-                    add  a0, a0, a1
-                    lw   a0, (a0)
-                    ret
-                ",
-                options(noreturn),
-            )
+    /// Issues a `fence w, o` before setting `msip` of `hart_idx`,
+    /// so that the receiver observes all prior memory writes once it sees the interrupt.
+    ///
+    /// [`set_msip`](Self::set_msip) provides no ordering beyond the volatile access itself.
+    #[inline]
+    pub fn set_msip_release(&self, hart_idx: usize) {
+        fence_before_mmio_write();
+        self.set_msip(hart_idx)
+    }
+
+    /// Reads `msip` of `hart_idx` and issues a `fence i, r` after it,
+    /// so that later memory reads observe what the sender wrote before the interrupt.
+    ///
+    /// [`read_msip`](Self::read_msip) provides no ordering beyond the volatile access itself.
+    #[inline]
+    pub fn read_msip_acquire(&self, hart_idx: usize) -> bool {
+        let ans = self.read_msip(hart_idx);
+        fence_after_mmio_read();
+        ans
+    }
+
+    /// Lazily yields the harts in `harts` whose `msip` is set.
+    ///
+    /// `harts` is clipped to the device capacity `N`.
+    #[inline]
+    pub fn pending_msips(&self, harts: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        (harts.start..harts.end.min(N)).filter(move |&i| self.read_msip(i))
+    }
+
+    /// Sets `msip` of every hart whose bit is set in `mask`.
+    ///
+    /// Bit `j` of `mask[i]` stands for hart `i * usize::BITS + j`.
+    /// Bits of harts beyond the device capacity `N` are ignored.
+    pub fn set_msip_mask(&self, mask: &[usize]) {
+        for (i, &word) in mask.iter().enumerate() {
+            let mut bits = word;
+            while bits != 0 {
+                let hart_idx = i * usize::BITS as usize + bits.trailing_zeros() as usize;
+                match self.0.get(hart_idx) {
+                    Some(reg) => unsafe { reg.0.get().write_le(1) },
+                    None => return,
+                }
+                bits &= bits - 1;
+            }
         }
     }
 
-    #[naked]
-    pub extern "C" fn set_msip_naked(&self, hart_idx: usize) {
-        unsafe {
-            asm!(
-                "   slli a1, a1, 2
-                    add  a0, a0, a1
-                    addi a1, zero, 1
-                    sw   a1, (a0)
-                    ret
-                ",
-                options(noreturn),
-            )
+    /// Sets `msip` of every hart in `harts` for which `pred` returns `true`.
+    ///
+    /// `harts` is clipped to the device capacity `N`.
+    #[inline]
+    pub fn send_ipi_where(&self, harts: Range<usize>, pred: impl Fn(usize) -> bool) {
+        for i in (harts.start..harts.end.min(N)).filter(|&i| pred(i)) {
+            // SAFETY: `i` is below `N`.
+            unsafe { self.set_msip_unchecked(i) };
         }
     }
 
-    #[naked]
-    pub extern "C" fn clear_msip_naked(&self, hart_idx: usize) {
-        unsafe {
-            asm!(
-                "   slli a1, a1, 2
-                    add  a0, a0, a1
-                    sw   zero, (a0)
-                    ret
-                ",
-                options(noreturn),
-            )
+    /// Clears `msip` of harts `0..hart_count`, so that IPIs left pending by a previous
+    /// boot stage or kernel do not fire once `mie.MSIE` is set, a usual boot-time step.
+    ///
+    /// Harts beyond `hart_count` are left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hart_count` exceeds the device capacity `N`.
+    #[inline]
+    pub fn clear_all_msip(&self, hart_count: usize) {
+        assert!(hart_count <= N, "hart count {hart_count} exceeds {N} harts");
+        for reg in &self.0[..hart_count] {
+            unsafe { reg.0.get().write_le(0) };
         }
     }
+
+    #[inline]
+    pub fn msip_ptr(&self, hart_idx: usize) -> *const u32 {
+        check_hart::<N>(hart_idx);
+        self.0[hart_idx].0.get()
+    }
+
+    #[inline]
+    pub fn msip_mut_ptr(&self, hart_idx: usize) -> *mut u32 {
+        check_hart::<N>(hart_idx);
+        self.0[hart_idx].0.get()
+    }
+
+    /// Like [`set_msip`](Self::set_msip), but fails with [`ClintError::HartOutOfRange`] if `hart_idx` is out of range.
+    #[inline]
+    pub fn try_set_msip(&self, hart_idx: usize) -> Result<(), ClintError> {
+        let reg = self.0.get(hart_idx).ok_or(ClintError::HartOutOfRange {
+            hart: hart_idx,
+            max: N,
+        })?;
+        unsafe { reg.0.get().write_le(1) };
+        Ok(())
+    }
+
+    /// Like [`clear_msip`](Self::clear_msip), but fails with [`ClintError::HartOutOfRange`] if `hart_idx` is out of range.
+    #[inline]
+    pub fn try_clear_msip(&self, hart_idx: usize) -> Result<(), ClintError> {
+        let reg = self.0.get(hart_idx).ok_or(ClintError::HartOutOfRange {
+            hart: hart_idx,
+            max: N,
+        })?;
+        unsafe { reg.0.get().write_le(0) };
+        Ok(())
+    }
 }
 
-#[test]
-fn test() {
-    assert_eq!(core::mem::size_of::<MSWI>(), 0x3ffc);
-    assert_eq!(core::mem::size_of::<SSWI>(), 0x3ffc);
-    assert_eq!(core::mem::size_of::<MTIMER>(), 0x7ff8);
-    assert_eq!(core::mem::size_of::<SifiveClint>(), 0xc000);
+impl<const H: usize> SifiveClint<H> {
+    /// Byte offset of the MSWI region from the CLINT base.
+    pub const MSWI_OFFSET: usize = 0;
+    /// Byte offset of the MTIMER region, i.e. of `mtimecmp[0]`, from the CLINT base.
+    pub const MTIMER_OFFSET: usize = {
+        assert_capacity(H);
+        size_of::<MSWI<H>>()
+    };
+    /// Byte offset of `mtime` from the CLINT base.
+    pub const MTIME_OFFSET: usize = Self::MTIMER_OFFSET + MTIMER::<H>::MTIME_OFFSET;
+
+    /// Byte offset of `msip` of `hart_idx` from the CLINT base.
+    ///
+    /// Like the other offset functions, this is usable in `const` items and `asm!` `const` operands.
+    #[inline]
+    pub const fn msip_offset(hart_idx: usize) -> usize {
+        Self::MSWI_OFFSET + MSWI::<H>::msip_offset(hart_idx)
+    }
+
+    /// Byte offset of `mtimecmp` of `hart_idx` from the CLINT base.
+    #[inline]
+    pub const fn mtimecmp_offset(hart_idx: usize) -> usize {
+        Self::MTIMER_OFFSET + MTIMER::<H>::mtimecmp_offset(hart_idx)
+    }
+
+    /// Byte offset of `mtime` from the CLINT base.
+    #[inline]
+    pub const fn mtime_offset() -> usize {
+        Self::MTIME_OFFSET
+    }
+
+    /// Length in bytes of the CLINT register window, `0xc000`.
+    #[inline]
+    pub const fn region_len() -> usize {
+        const { assert_capacity(H) };
+        size_of::<Self>()
+    }
+
+    /// Start and end of the CLINT at `base`, e.g. for a PMP entry or linker script.
+    ///
+    /// `0xc000` is not a power of two, so a NAPOT entry has to cover `0x10000` bytes instead,
+    /// or a TOR entry pair can use these bounds exactly.
+    #[inline]
+    pub const fn pmp_region(base: usize) -> (usize, usize) {
+        (base, base + Self::region_len())
+    }
+
+    /// Yields the hart indices `0..max`, clipped to the device capacity `H`.
+    #[inline]
+    pub fn hart_indices(max: usize) -> impl Iterator<Item = usize> {
+        0..max.min(H)
+    }
+
+    /// Returns a reference to the CLINT mapped at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be 16 KiB aligned and the whole `size_of::<Self>()`-byte region starting at it
+    /// must be a valid CLINT MMIO window for the rest of the program.
+    #[inline]
+    pub const unsafe fn from_base_addr(base: usize) -> &'static Self {
+        &*Self::ptr_from_base_addr(base)
+    }
+
+    /// Like [`from_base_addr`](Self::from_base_addr), but checks `base` first.
+    ///
+    /// `base` must be non-null and aligned to `align_of::<Self>()`, which is 16 KiB.
+    /// That also covers the 8-byte alignment `mtime` and `mtimecmp` need,
+    /// whose misaligned accesses may trap or be emulated slowly.
+    ///
+    /// # Safety
+    ///
+    /// Apart from the alignment, the requirements of [`from_base_addr`](Self::from_base_addr) apply.
+    #[inline]
+    pub unsafe fn try_from_base_addr(base: usize) -> Result<&'static Self, AlignmentError> {
+        let align = align_of::<Self>();
+        if base == 0 {
+            Err(AlignmentError::Null)
+        } else if base & (align - 1) != 0 {
+            Err(AlignmentError::Misaligned { base, align })
+        } else {
+            Ok(Self::from_base_addr(base))
+        }
+    }
+
+    /// Casts `base` into a pointer to the CLINT.
+    #[inline]
+    pub const fn ptr_from_base_addr(base: usize) -> *const Self {
+        base as *const Self
+    }
+
+    /// Casts `base` into a mutable pointer to the CLINT.
+    #[inline]
+    pub const fn mut_ptr_from_base_addr(base: usize) -> *mut Self {
+        base as *mut Self
+    }
+
+    /// Returns the start and length in bytes of the register window, e.g. for dumping it from asm.
+    #[inline]
+    pub fn raw_bytes(&self) -> (*const u8, usize) {
+        ((self as *const Self).cast(), size_of::<Self>())
+    }
+
+    /// Views the whole register window as bytes, `0xc000` of them for [`SifiveClint4095`].
+    ///
+    /// Intended for dumping the device over a debug UART or comparing it with a golden image.
+    /// `msip`, `mtimecmp` and `mtime` reads have no side effects, so a full dump is acceptable.
+    ///
+    /// # Safety
+    ///
+    /// The slice bypasses volatile accesses, so the compiler may merge or elide its reads,
+    /// and the window must contain no registers with read side effects.
+    #[inline]
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        let (ptr, len) = self.raw_bytes();
+        core::slice::from_raw_parts(ptr, len)
+    }
+
+    #[inline]
+    pub fn read_mtime(&self) -> u64 {
+        self.mtimer.read_mtime()
+    }
+
+    /// See [`MTIMER::read_mtime_asm`](MTIMER::read_mtime_asm).
+    #[cfg(target_arch = "riscv64")]
+    #[inline(always)]
+    pub fn read_mtime_asm(&self) -> u64 {
+        self.mtimer.read_mtime_asm()
+    }
+
+    /// See [`MTIMER::read_mtime_fenced`](MTIMER::read_mtime_fenced).
+    #[inline]
+    pub fn read_mtime_fenced(&self) -> u64 {
+        self.mtimer.read_mtime_fenced()
+    }
+
+    /// See [`MTIMER::read_mtime_monotonic`](MTIMER::read_mtime_monotonic).
+    #[inline]
+    pub fn read_mtime_monotonic(&self) -> u64 {
+        self.mtimer.read_mtime_monotonic()
+    }
+
+    /// See [`MTIMER::measure_read_overhead`](MTIMER::measure_read_overhead).
+    #[inline]
+    pub fn measure_read_overhead(&self, iters: usize) -> u64 {
+        self.mtimer.measure_read_overhead(iters)
+    }
+
+    /// See [`MTIMER::read_mtime_rv32`](MTIMER::read_mtime_rv32).
+    #[inline]
+    pub fn read_mtime_rv32(&self) -> u64 {
+        self.mtimer.read_mtime_rv32()
+    }
+
+    #[inline]
+    pub fn read_mtime_lo(&self) -> u32 {
+        self.mtimer.read_mtime_lo()
+    }
+
+    #[inline]
+    pub fn read_mtime_hi(&self) -> u32 {
+        self.mtimer.read_mtime_hi()
+    }
+
+    /// See [`MTIMER::freeze_mtime`](MTIMER::freeze_mtime).
+    #[inline]
+    pub fn freeze_mtime(&self) -> u64 {
+        self.mtimer.freeze_mtime()
+    }
+
+    /// See [`MTIMER::thaw_mtime`](MTIMER::thaw_mtime).
+    #[inline]
+    pub fn thaw_mtime(&self, frozen: u64) {
+        self.mtimer.thaw_mtime(frozen)
+    }
+
+    /// See [`MTIMER::with_frozen_mtime`](MTIMER::with_frozen_mtime).
+    #[inline]
+    pub fn with_frozen_mtime<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.mtimer.with_frozen_mtime(f)
+    }
+
+    /// See [`MTIMER::write_mtime_lo`](MTIMER::write_mtime_lo).
+    #[inline]
+    pub fn write_mtime_lo(&self, val: u32) {
+        self.mtimer.write_mtime_lo(val)
+    }
+
+    /// See [`MTIMER::write_mtime_hi`](MTIMER::write_mtime_hi).
+    #[inline]
+    pub fn write_mtime_hi(&self, val: u32) {
+        self.mtimer.write_mtime_hi(val)
+    }
+
+    /// See [`MTIMER::write_mtimecmp_rv32_safe`](MTIMER::write_mtimecmp_rv32_safe).
+    #[inline]
+    pub fn write_mtimecmp_rv32_safe(&self, hart_idx: usize, val: u64) {
+        self.mtimer.write_mtimecmp_rv32_safe(hart_idx, val)
+    }
+
+    #[inline]
+    pub fn write_mtime(&self, val: u64) {
+        self.mtimer.write_mtime(val)
+    }
+
+    /// See [`MTIMER::fmt_mtime`](MTIMER::fmt_mtime).
+    #[inline]
+    pub fn fmt_mtime<'b>(&self, buf: &'b mut [u8; 20]) -> &'b str {
+        self.mtimer.fmt_mtime(buf)
+    }
+
+    /// See [`MTIMER::init_mtime`](MTIMER::init_mtime).
+    #[inline]
+    pub fn init_mtime(&self) {
+        self.mtimer.init_mtime()
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+        self.mtimer.read_mtimecmp(hart_idx)
+    }
+
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+        self.mtimer.write_mtimecmp(hart_idx, val)
+    }
+
+    #[inline]
+    pub fn mtime_ptr(&self) -> *const u64 {
+        self.mtimer.mtime_ptr()
+    }
+
+    #[inline]
+    pub fn mtime_mut_ptr(&self) -> *mut u64 {
+        self.mtimer.mtime_mut_ptr()
+    }
+
+    #[inline]
+    pub fn mtimecmp_ptr(&self, hart_idx: usize) -> *const u64 {
+        self.mtimer.mtimecmp_ptr(hart_idx)
+    }
+
+    #[inline]
+    pub fn mtimecmp_mut_ptr(&self, hart_idx: usize) -> *mut u64 {
+        self.mtimer.mtimecmp_mut_ptr(hart_idx)
+    }
+
+    /// See [`MSWI::set_msip_release`](MSWI::set_msip_release).
+    #[inline]
+    pub fn set_msip_release(&self, hart_idx: usize) {
+        self.mswi.set_msip_release(hart_idx)
+    }
+
+    /// See [`MSWI::read_msip_acquire`](MSWI::read_msip_acquire).
+    #[inline]
+    pub fn read_msip_acquire(&self, hart_idx: usize) -> bool {
+        self.mswi.read_msip_acquire(hart_idx)
+    }
+
+    /// See [`MSWI::pending_msips`](MSWI::pending_msips).
+    #[inline]
+    pub fn pending_msips(&self, harts: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        self.mswi.pending_msips(harts)
+    }
+
+    /// See [`MSWI::set_msip_mask`](MSWI::set_msip_mask).
+    #[inline]
+    pub fn set_msip_mask(&self, mask: &[usize]) {
+        self.mswi.set_msip_mask(mask)
+    }
+
+    /// See [`MSWI::send_ipi_where`](MSWI::send_ipi_where).
+    #[inline]
+    pub fn send_ipi_where(&self, harts: Range<usize>, pred: impl Fn(usize) -> bool) {
+        self.mswi.send_ipi_where(harts, pred)
+    }
+
+    #[inline]
+    pub fn msip_ptr(&self, hart_idx: usize) -> *const u32 {
+        self.mswi.msip_ptr(hart_idx)
+    }
+
+    #[inline]
+    pub fn msip_mut_ptr(&self, hart_idx: usize) -> *mut u32 {
+        self.mswi.msip_mut_ptr(hart_idx)
+    }
+
+    /// See [`MTIMER::compare_exchange_mtimecmp`](MTIMER::compare_exchange_mtimecmp),
+    /// only available with `cfg(target_has_atomic = "64")`.
+    #[cfg(target_has_atomic = "64")]
+    #[inline]
+    pub fn compare_exchange_mtimecmp(
+        &self,
+        hart_idx: usize,
+        current: u64,
+        new: u64,
+    ) -> Result<u64, u64> {
+        self.mtimer
+            .compare_exchange_mtimecmp(hart_idx, current, new)
+    }
+
+    /// See [`MTIMER::clear_mtimecmp`](MTIMER::clear_mtimecmp).
+    #[inline]
+    pub fn clear_mtimecmp(&self, hart_idx: usize) {
+        self.mtimer.clear_mtimecmp(hart_idx)
+    }
+
+    /// See [`MTIMER::swap_mtimecmp`](MTIMER::swap_mtimecmp).
+    #[inline]
+    pub fn swap_mtimecmp(&self, hart_idx: usize, new: u64) -> u64 {
+        self.mtimer.swap_mtimecmp(hart_idx, new)
+    }
+
+    /// See [`MTIMER::set_mtimecmp_after`](MTIMER::set_mtimecmp_after).
+    #[inline]
+    pub fn set_mtimecmp_after(&self, hart_idx: usize, delta: u64) {
+        self.mtimer.set_mtimecmp_after(hart_idx, delta)
+    }
+
+    /// See [`MTIMER::set_mtimecmp_after_saturating`](MTIMER::set_mtimecmp_after_saturating).
+    #[inline]
+    pub fn set_mtimecmp_after_saturating(&self, hart_idx: usize, delta: u64) {
+        self.mtimer.set_mtimecmp_after_saturating(hart_idx, delta)
+    }
+
+    /// See [`MTIMER::set_mtimecmp_after_wrapping`](MTIMER::set_mtimecmp_after_wrapping).
+    #[inline]
+    pub fn set_mtimecmp_after_wrapping(&self, hart_idx: usize, delta: u64) {
+        self.mtimer.set_mtimecmp_after_wrapping(hart_idx, delta)
+    }
+
+    /// See [`MTIMER::advance_mtimecmp`](MTIMER::advance_mtimecmp).
+    #[inline]
+    pub fn advance_mtimecmp(&self, hart_idx: usize, period: u64) {
+        self.mtimer.advance_mtimecmp(hart_idx, period)
+    }
+
+    /// See [`MTIMER::busy_wait_until`](MTIMER::busy_wait_until).
+    #[inline]
+    pub fn busy_wait_until(&self, deadline: u64) {
+        self.mtimer.busy_wait_until(deadline)
+    }
+
+    /// See [`MTIMER::busy_wait_ticks`](MTIMER::busy_wait_ticks).
+    #[inline]
+    pub fn busy_wait_ticks(&self, ticks: u64) {
+        self.mtimer.busy_wait_ticks(ticks)
+    }
+
+    #[inline]
+    pub fn try_read_mtimecmp(&self, hart_idx: usize) -> Result<u64, ClintError> {
+        self.mtimer.try_read_mtimecmp(hart_idx)
+    }
+
+    #[inline]
+    pub fn try_write_mtimecmp(&self, hart_idx: usize, val: u64) -> Result<(), ClintError> {
+        self.mtimer.try_write_mtimecmp(hart_idx, val)
+    }
+
+    /// See [`MTIMER::read_mtimecmp_unchecked`](MTIMER::read_mtimecmp_unchecked).
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than `H`.
+    #[inline]
+    pub unsafe fn read_mtimecmp_unchecked(&self, hart_idx: usize) -> u64 {
+        self.mtimer.read_mtimecmp_unchecked(hart_idx)
+    }
+
+    /// See [`MTIMER::write_mtimecmp_unchecked`](MTIMER::write_mtimecmp_unchecked).
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than `H`.
+    #[inline]
+    pub unsafe fn write_mtimecmp_unchecked(&self, hart_idx: usize, val: u64) {
+        self.mtimer.write_mtimecmp_unchecked(hart_idx, val)
+    }
+
+    /// See [`MTIMER::write_mtimecmp_range`](MTIMER::write_mtimecmp_range).
+    #[inline]
+    pub fn write_mtimecmp_range(&self, harts: Range<usize>, val: u64) {
+        self.mtimer.write_mtimecmp_range(harts, val)
+    }
+
+    /// See [`MTIMER::disable_all_timers`](MTIMER::disable_all_timers).
+    #[inline]
+    pub fn disable_all_timers(&self, hart_count: usize) {
+        self.mtimer.disable_all_timers(hart_count)
+    }
+
+    /// See [`MSWI::clear_all_msip`](MSWI::clear_all_msip).
+    #[inline]
+    pub fn clear_all_msip(&self, hart_count: usize) {
+        self.mswi.clear_all_msip(hart_count)
+    }
+
+    /// See [`MTIMER::mtimecmp_iter`](MTIMER::mtimecmp_iter).
+    #[inline]
+    pub fn mtimecmp_iter(&self, harts: Range<usize>) -> impl Iterator<Item = u64> + '_ {
+        self.mtimer.mtimecmp_iter(harts)
+    }
+
+    /// See [`MTIMER::is_timer_expired`](MTIMER::is_timer_expired).
+    #[inline]
+    pub fn is_timer_expired(&self, hart_idx: usize) -> bool {
+        self.mtimer.is_timer_expired(hart_idx)
+    }
+
+    /// See [`MTIMER::ticks_until_deadline`](MTIMER::ticks_until_deadline).
+    #[inline]
+    pub fn ticks_until_deadline(&self, hart_idx: usize) -> Option<u64> {
+        self.mtimer.ticks_until_deadline(hart_idx)
+    }
+
+    /// See [`MTIMER::expired_timers`](MTIMER::expired_timers).
+    #[inline]
+    pub fn expired_timers(&self, harts: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        self.mtimer.expired_timers(harts)
+    }
+
+    #[inline]
+    pub fn read_msip(&self, hart_idx: usize) -> bool {
+        self.mswi.read_msip(hart_idx)
+    }
+
+    /// See [`MSWI::read_msip_raw`](MSWI::read_msip_raw).
+    #[inline]
+    pub fn read_msip_raw(&self, hart_idx: usize) -> u32 {
+        self.mswi.read_msip_raw(hart_idx)
+    }
+
+    /// See [`MSWI::write_msip_raw`](MSWI::write_msip_raw).
+    #[inline]
+    pub fn write_msip_raw(&self, hart_idx: usize, val: u32) {
+        self.mswi.write_msip_raw(hart_idx, val)
+    }
+
+    #[inline]
+    pub fn set_msip(&self, hart_idx: usize) {
+        self.mswi.set_msip(hart_idx)
+    }
+
+    #[inline]
+    pub fn clear_msip(&self, hart_idx: usize) {
+        self.mswi.clear_msip(hart_idx)
+    }
+
+    /// Sets `msip` of `target` and spins until it reads clear again,
+    /// for at most `timeout_ticks` ticks of `mtime`.
+    ///
+    /// This relies on the receiver clearing its own `msip` once it has handled the IPI.
+    /// Fails with [`TimeoutError`] if that does not happen in time.
+    pub fn send_and_wait_ack(&self, target: usize, timeout_ticks: u64) -> Result<(), TimeoutError> {
+        let deadline = self.read_mtime().saturating_add(timeout_ticks);
+        self.set_msip(target);
+        loop {
+            if !self.read_msip(target) {
+                return Ok(());
+            }
+            if self.read_mtime() >= deadline {
+                return Err(TimeoutError);
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Checks that the device behaves like a CLINT, to catch wrong base addresses
+    /// and address decode bugs during bring-up.
+    ///
+    /// This writes a test pattern to `mtimecmp` of hart 0 and reads it back,
+    /// toggles `msip` of hart 0, and checks that the reserved word of the MSWI reads as zero.
+    /// Both registers are restored before returning, including on failure,
+    /// but while the check runs hart 0 may see a spurious software interrupt
+    /// and its timer interrupt is held off.
+    ///
+    /// This is a bring-up aid, not for production hot paths.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `H` is zero.
+    pub fn self_check(&self) -> Result<(), SelfCheckError> {
+        const PATTERN: u64 = 0xfedc_ba98_7654_3210;
+
+        let mtimecmp = self.read_mtimecmp(0);
+        self.write_mtimecmp(0, PATTERN);
+        let read = self.read_mtimecmp(0);
+        self.write_mtimecmp(0, mtimecmp);
+        if read != PATTERN {
+            return Err(SelfCheckError::MtimecmpMismatch {
+                wrote: PATTERN,
+                read,
+            });
+        }
+
+        let msip = self.read_msip(0);
+        let write_msip = |val: bool| {
+            if val {
+                self.set_msip(0)
+            } else {
+                self.clear_msip(0)
+            }
+        };
+        for wrote in [!msip, msip] {
+            write_msip(wrote);
+            let read = self.read_msip(0);
+            if read != wrote {
+                write_msip(msip);
+                return Err(SelfCheckError::MsipMismatch { wrote, read });
+            }
+        }
+
+        match self.mswi.read_reserved() {
+            0 => Ok(()),
+            value => Err(SelfCheckError::ReservedNonZero(value)),
+        }
+    }
+
+    /// See [`MSWI::set_msip_unchecked`](MSWI::set_msip_unchecked).
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than `H`.
+    #[inline]
+    pub unsafe fn set_msip_unchecked(&self, hart_idx: usize) {
+        self.mswi.set_msip_unchecked(hart_idx)
+    }
+
+    /// See [`MSWI::clear_msip_unchecked`](MSWI::clear_msip_unchecked).
+    ///
+    /// # Safety
+    ///
+    /// `hart_idx` must be less than `H`.
+    #[inline]
+    pub unsafe fn clear_msip_unchecked(&self, hart_idx: usize) {
+        self.mswi.clear_msip_unchecked(hart_idx)
+    }
+
+    /// See [`MSWI::read_own_msip`](MSWI::read_own_msip).
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    #[inline]
+    pub fn read_own_msip(&self) -> bool {
+        self.mswi.read_own_msip()
+    }
+
+    /// See [`MSWI::clear_own_msip`](MSWI::clear_own_msip).
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    #[inline]
+    pub fn clear_own_msip(&self) {
+        self.mswi.clear_own_msip()
+    }
+
+    /// Sends an IPI to each hart of `targets`, then spins until `ack` reaches the number of
+    /// targets, for at most `timeout_ticks` ticks of `mtime`, like SBI remote fences need.
+    ///
+    /// Each receiver is expected to increment `ack` once it has handled the IPI,
+    /// e.g. performed the requested fence; the caller resets `ack` before the next round.
+    /// IPIs are sent with [`set_msip_release`](MSWI::set_msip_release),
+    /// so receivers observe the request written before the call.
+    /// Fails with [`TimeoutError`] if not all harts acknowledged in time.
+    pub fn broadcast_ipi_and_wait(
+        &self,
+        targets: impl Iterator<Item = usize>,
+        ack: &core::sync::atomic::AtomicUsize,
+        timeout_ticks: u64,
+    ) -> Result<(), TimeoutError> {
+        use core::sync::atomic::Ordering;
+        let deadline = self.read_mtime().saturating_add(timeout_ticks);
+        let mut count = 0;
+        for hart_idx in targets {
+            self.mswi.set_msip_release(hart_idx);
+            count += 1;
+        }
+        while ack.load(Ordering::Acquire) < count {
+            if self.read_mtime() >= deadline {
+                return Err(TimeoutError);
+            }
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+
+    /// See [`MSWI::take_msip`](MSWI::take_msip).
+    #[inline]
+    pub fn take_msip(&self, hart_idx: usize) -> bool {
+        self.mswi.take_msip(hart_idx)
+    }
+
+    #[inline]
+    pub fn try_set_msip(&self, hart_idx: usize) -> Result<(), ClintError> {
+        self.mswi.try_set_msip(hart_idx)
+    }
+
+    #[inline]
+    pub fn try_clear_msip(&self, hart_idx: usize) -> Result<(), ClintError> {
+        self.mswi.try_clear_msip(hart_idx)
+    }
+}
+
+impl<const N: usize> SSWI<N> {
+    /// Byte offset of the reserved word at the end of the SSWI window.
+    pub const RESERVED_OFFSET: usize = 0x3ffc;
+
+    /// Length in bytes of the SSWI register window.
+    #[inline]
+    pub const fn region_len() -> usize {
+        const { assert_capacity(N) };
+        size_of::<Self>()
+    }
+
+    /// Byte offset of `setssip` of `hart_idx` from the SSWI base.
+    #[inline]
+    pub const fn setssip_offset(hart_idx: usize) -> usize {
+        const { assert_capacity(N) };
+        hart_idx * size_of::<SETSSIP>()
+    }
+
+    /// Reads the reserved word at [`RESERVED_OFFSET`](Self::RESERVED_OFFSET).
+    ///
+    /// Diagnostic only, for checking the address decode during bring-up:
+    /// hardware usually reads this word as zero.
+    /// No MTIMER follows an SSWI, so any other value means the base address is wrong
+    /// or the decoder aliases the window onto another device.
+    /// It is available in release builds too, like [`MSWI::read_reserved`].
+    #[inline]
+    pub fn read_reserved(&self) -> u32 {
+        // SAFETY: the alignment pads `Self` to its 16 KiB window.
+        unsafe { read_reserved_word(self) }
+    }
+
+    /// Returns a reference to the SSWI mapped at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be 16 KiB aligned and the whole `size_of::<Self>()`-byte region starting at it
+    /// must be a valid SSWI MMIO window for the rest of the program.
+    #[inline]
+    pub const unsafe fn from_base_addr(base: usize) -> &'static Self {
+        &*(base as *const Self)
+    }
+
+    /// Reads the `SETSSIP` register of `hart_idx`.
+    ///
+    /// Conformant ACLINT devices always read back zero,
+    /// the pending state lives in `sip.SSIP` of the target hart.
+    #[inline]
+    pub fn read_setssip(&self, hart_idx: usize) -> bool {
+        check_hart::<N>(hart_idx);
+        unsafe { self.0[hart_idx].0.get().read_le() != 0 }
+    }
+
+    /// Writes exactly `1` to the `SETSSIP` register of `hart_idx`,
+    /// making the supervisor software interrupt of that hart pending.
+    ///
+    /// `SETSSIP` is write-1-to-set: conformant hardware ignores writes of `0`,
+    /// so there is no way to clear the interrupt through this device.
+    /// The receiving hart clears it through `sip.SSIP` instead.
+    #[inline]
+    pub fn set_setssip(&self, hart_idx: usize) {
+        check_hart::<N>(hart_idx);
+        unsafe { self.0[hart_idx].0.get().write_le(1) }
+    }
+}
+
+/// Reads the `mhartid` CSR of the current hart.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline(always)]
+fn mhartid() -> usize {
+    let ans: usize;
+    unsafe { core::arch::asm!("csrr {}, mhartid", out(reg) ans, options(nomem, nostack)) };
+    ans
+}
+
+/// Reads the reserved word at the end of the MSWI or SSWI `dev`.
+///
+/// # Safety
+///
+/// `dev` must span the whole 16 KiB window of a software interrupt device.
+#[inline]
+unsafe fn read_reserved_word<T>(dev: &T) -> u32 {
+    (dev as *const T)
+        .cast::<u8>()
+        .add(MSWI4095::RESERVED_OFFSET)
+        .cast::<u32>()
+        .read_le()
+}
+
+/// Formats `val` into the tail of `buf`, which fits the 20 digits of `u64::MAX`.
+fn fmt_decimal(mut val: u64, buf: &mut [u8; 20]) -> &str {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (val % 10) as u8;
+        val /= 10;
+        if val == 0 {
+            break;
+        }
+    }
+    // SAFETY: only ASCII digits were written.
+    unsafe { core::str::from_utf8_unchecked(&buf[start..]) }
+}
+
+/// Orders prior memory writes before a following MMIO write.
+#[inline(always)]
+fn fence_before_mmio_write() {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("fence w, o", options(nostack))
+    }
+    #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+    core::sync::atomic::fence(core::sync::atomic::Ordering::Release)
+}
+
+/// Orders a preceding MMIO read before later memory reads.
+#[inline(always)]
+fn fence_after_mmio_read() {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("fence i, r", options(nostack))
+    }
+    #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+    core::sync::atomic::fence(core::sync::atomic::Ordering::Acquire)
+}
+
+/// Allocates a zeroed `T` on the heap.
+///
+/// # Safety
+///
+/// All-zero bytes must be a valid `T`.
+#[cfg(any(test, feature = "std"))]
+unsafe fn new_zeroed_box<T>() -> std::boxed::Box<T> {
+    use std::alloc::{alloc_zeroed, handle_alloc_error, Layout};
+    let layout = Layout::new::<T>();
+    let ptr = alloc_zeroed(layout).cast::<T>();
+    if ptr.is_null() {
+        handle_alloc_error(layout);
+    }
+    std::boxed::Box::from_raw(ptr)
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<const H: usize> SifiveClint<H> {
+    /// Allocates a zeroed CLINT on the heap, for testing against ordinary memory.
+    ///
+    /// # Safety
+    ///
+    /// The device must not be accessed from more than one thread at a time:
+    /// unlike MMIO, concurrent accesses to ordinary memory are data races.
+    pub unsafe fn new_boxed() -> std::boxed::Box<Self> {
+        // SAFETY: all registers are plain integers, so zeroed memory is a valid `SifiveClint`.
+        new_zeroed_box()
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<const N: usize> MTIMER<N> {
+    /// Allocates a zeroed MTIMER on the heap, for testing against ordinary memory.
+    ///
+    /// # Safety
+    ///
+    /// See [`SifiveClint::new_boxed`].
+    pub unsafe fn new_boxed() -> std::boxed::Box<Self> {
+        new_zeroed_box()
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<const N: usize> MSWI<N> {
+    /// Allocates a zeroed MSWI on the heap, for testing against ordinary memory.
+    ///
+    /// # Safety
+    ///
+    /// See [`SifiveClint::new_boxed`].
+    pub unsafe fn new_boxed() -> std::boxed::Box<Self> {
+        new_zeroed_box()
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<const N: usize> SSWI<N> {
+    /// Allocates a zeroed SSWI on the heap, for testing against ordinary memory.
+    ///
+    /// # Safety
+    ///
+    /// See [`SifiveClint::new_boxed`].
+    pub unsafe fn new_boxed() -> std::boxed::Box<Self> {
+        new_zeroed_box()
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl<const N: usize> Rv32Mtimer<N> {
+    /// Allocates a zeroed MTIMER on the heap, for testing against ordinary memory.
+    ///
+    /// # Safety
+    ///
+    /// See [`SifiveClint::new_boxed`].
+    pub unsafe fn new_boxed() -> std::boxed::Box<Self> {
+        new_zeroed_box()
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(core::mem::size_of::<[MSIP; MAX_HARTS]>(), 0x3ffc);
+    assert_eq!(core::mem::size_of::<MSWI4095>(), 0x4000);
+    assert_eq!(core::mem::size_of::<SSWI4095>(), 0x4000);
+    assert_eq!(core::mem::size_of::<MTIMER4095>(), 0x8000);
+    assert_eq!(core::mem::size_of::<SifiveClint4095>(), 0xc000);
+    assert_eq!(core::mem::size_of::<MSWI<8>>(), 0x4000);
+    assert_eq!(core::mem::size_of::<SifiveClint<8>>(), 0xc000);
+    assert_eq!(SifiveClint::<8>::mtime_offset(), 0xbff8);
+    assert_eq!(SifiveClint::<1>::mtimecmp_offset(0), 0x4000);
+    const _: () = assert!(SifiveClint::<8>::region_len() == 0xc000);
+    assert_eq!(MSWI::<8>::region_len(), 0x4000);
+    assert_eq!(MTIMER::<8>::region_len(), 0x8000);
+    assert_eq!(
+        SifiveClint4095::pmp_region(0x200_0000),
+        (0x200_0000, 0x200_c000)
+    );
+    assert_eq!(SifiveClint4095::msip_offset(1), 0x4);
+    assert_eq!(SifiveClint4095::mtimecmp_offset(1), 0x4008);
+    assert_eq!(SifiveClint4095::mtime_offset(), 0xbff8);
+    const _: () = assert!(MTIMER4095::mtime_offset() == 0x7ff8);
+    const _: () = assert!(SSWI4095::setssip_offset(4094) == 0x3ff8);
+    assert_eq!(core::mem::size_of::<[SETSSIP; MAX_HARTS]>(), 0x3ffc);
+    assert_eq!(core::mem::size_of::<SSWI<8>>(), 0x4000);
+    assert_eq!(SSWI4095::setssip_offset(0), 0);
+    assert_eq!(SSWI4095::RESERVED_OFFSET, 0x3ffc);
+    assert_eq!(MSWI4095::msip_offset(0), 0);
+    assert_eq!(MSWI4095::RESERVED_OFFSET, 0x3ffc);
+}
+
+#[test]
+fn test_mock() {
+    let clint = unsafe { SifiveClint4095::new_boxed() };
+    assert!(!clint.read_msip(3));
+    clint.set_msip(3);
+    assert!(clint.read_msip(3));
+    assert!(!clint.read_msip(2));
+    clint.clear_msip(3);
+    assert!(!clint.read_msip(3));
+
+    let base = &*clint as *const SifiveClint4095 as usize;
+    assert_eq!(
+        clint.msip_ptr(3) as usize - base,
+        SifiveClint4095::msip_offset(3)
+    );
+    assert_eq!(
+        clint.mtimecmp_ptr(3) as usize - base,
+        SifiveClint4095::mtimecmp_offset(3)
+    );
+    assert_eq!(
+        clint.mtime_ptr() as usize - base,
+        SifiveClint4095::mtime_offset()
+    );
+}
+
+#[test]
+fn test_mtimecmp_after() {
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    clint.write_mtime(100);
+    clint.set_mtimecmp_after(1, 50);
+    assert_eq!(clint.read_mtimecmp(1), 150);
+    clint.write_mtime(u64::MAX - 1);
+    clint.set_mtimecmp_after(1, 50);
+    assert_eq!(clint.read_mtimecmp(1), u64::MAX);
+    clint.set_mtimecmp_after_saturating(1, 2);
+    assert_eq!(clint.read_mtimecmp(1), u64::MAX);
+    clint.set_mtimecmp_after_wrapping(1, 2);
+    assert_eq!(clint.read_mtimecmp(1), 0);
+}
+
+#[test]
+fn test_mtime_halves() {
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    clint.write_mtime(0x1234_5678_9abc_def0);
+    assert_eq!(clint.read_mtime_lo(), 0x9abc_def0);
+    assert_eq!(clint.read_mtime_hi(), 0x1234_5678);
+    clint.write_mtime_hi(1);
+    clint.write_mtime_lo(2);
+    assert_eq!(clint.read_mtime_rv32(), 0x1_0000_0002);
+}
+
+#[test]
+fn test_frozen_mtime() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    clint.write_mtime(10);
+    let ans = clint.with_frozen_mtime(|| {
+        clint.write_mtime(500);
+        clint.read_mtime()
+    });
+    assert_eq!(ans, 500);
+    assert_eq!(clint.read_mtime(), 10);
+}
+
+#[test]
+fn test_msip_mask() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    clint.set_msip_mask(&[0b1001 | 1 << 8, usize::MAX]);
+    assert_eq!((0..8).filter(|&i| clint.read_msip(i)).count(), 2);
+    assert!(clint.read_msip(0));
+    assert!(clint.read_msip(3));
+    assert!(clint.pending_msips(1..100).eq([3]));
+    assert!(SifiveClint::<8>::hart_indices(100).eq(0..8));
+}
+
+#[test]
+fn test_expired_timers() {
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    clint.write_mtime(100);
+    clint.write_mtimecmp(0, 50);
+    clint.write_mtimecmp(1, 100);
+    clint.write_mtimecmp(2, 101);
+    clint.clear_mtimecmp(3);
+    assert!(clint.expired_timers(0..10).eq([0, 1]));
+    assert!(clint.expired_timers(1..3).eq([1]));
+    assert!(clint.is_timer_expired(1));
+    assert!(!clint.is_timer_expired(2));
+    assert!(clint.mtimecmp_iter(1..10).eq([100, 101, u64::MAX]));
+    assert_eq!(clint.mtimecmp_iter(0..4).position(|v| v > 100), Some(2));
+}
+
+#[test]
+fn test_read_reserved() {
+    assert_eq!(unsafe { MSWI4095::new_boxed() }.read_reserved(), 0);
+    assert_eq!(unsafe { SSWI::<2>::new_boxed() }.read_reserved(), 0);
+}
+
+#[test]
+fn test_swap_mtimecmp() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    clint.write_mtimecmp(1, 42);
+    assert_eq!(clint.swap_mtimecmp(1, 7), 42);
+    assert_eq!(clint.swap_mtimecmp(1, 9), 7);
+    assert_eq!(clint.read_mtimecmp(1), 9);
+    assert_eq!(clint.read_mtimecmp(0), 0);
+    unsafe { clint.write_mtimecmp_unchecked(0, 4) };
+    assert_eq!(unsafe { clint.read_mtimecmp_unchecked(0) }, 4);
+}
+
+#[test]
+fn test_busy_wait() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    clint.write_mtime(100);
+    clint.busy_wait_until(100);
+    clint.busy_wait_until(50);
+    clint.busy_wait_ticks(0);
+}
+
+#[test]
+fn test_as_bytes() {
+    let clint = unsafe { SifiveClint4095::new_boxed() };
+    clint.set_msip(1);
+    clint.write_mtime(0x0102);
+    let bytes = unsafe { clint.as_bytes() };
+    assert_eq!(bytes.len(), 0xc000);
+    assert_eq!(bytes[SifiveClint4095::msip_offset(1)], 1);
+    assert_eq!(bytes[SifiveClint4095::mtime_offset()..][..2], [2, 1]);
+}
+
+#[test]
+fn test_sswi() {
+    let sswi = unsafe { SSWI::<4>::new_boxed() };
+    sswi.set_setssip(2);
+    assert!(sswi.read_setssip(2));
+    assert!(!sswi.read_setssip(1));
+}
+
+#[test]
+fn test_checked_errors() {
+    use std::string::ToString;
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    assert_eq!(clint.try_write_mtimecmp(1, 3), Ok(()));
+    assert_eq!(clint.try_read_mtimecmp(1), Ok(3));
+    let err = clint.try_set_msip(2).unwrap_err();
+    assert_eq!(err, ClintError::HartOutOfRange { hart: 2, max: 2 });
+    assert_eq!(err.to_string(), "hart 2 out of range, device has 2 harts");
+}
+
+#[test]
+fn test_write_mtimecmp_range() {
+    let clint = unsafe { SifiveClint::<6>::new_boxed() };
+    clint.write_mtimecmp_range(1..4, 99);
+    let vals: std::vec::Vec<_> = (0..6).map(|i| clint.read_mtimecmp(i)).collect();
+    assert_eq!(vals, [0, 99, 99, 99, 0, 0]);
+}
+
+#[test]
+#[should_panic]
+fn test_write_mtimecmp_range_oob() {
+    unsafe { SifiveClint::<6>::new_boxed() }.write_mtimecmp_range(0..7, 0);
+}
+
+#[test]
+fn test_fmt_mtime() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    let mut buf = [0; 20];
+    assert_eq!(clint.fmt_mtime(&mut buf), "0");
+    clint.write_mtime(1234567890);
+    assert_eq!(clint.fmt_mtime(&mut buf), "1234567890");
+    clint.write_mtime(u64::MAX);
+    assert_eq!(clint.fmt_mtime(&mut buf), "18446744073709551615");
+}
+
+#[test]
+fn test_write_msip_raw() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    clint.write_msip_raw(1, 0xdead_beef);
+    assert_eq!(clint.read_msip_raw(1), 0xdead_beef);
+    clint.write_msip_raw(1, 0);
+    assert!(!clint.read_msip(1));
+}
+
+#[test]
+fn test_take_msip() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    clint.set_msip(1);
+    assert!(clint.take_msip(1));
+    assert!(!clint.take_msip(1));
+    assert!(!clint.read_msip(1));
+    unsafe { clint.set_msip_unchecked(0) };
+    assert!(clint.read_msip(0));
+    unsafe { clint.clear_msip_unchecked(0) };
+    assert!(!clint.read_msip(0));
+}
+
+#[test]
+fn test_try_from_base_addr() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    let base = &*clint as *const SifiveClint<1> as usize;
+    assert!(unsafe { SifiveClint::<1>::try_from_base_addr(base) }.is_ok());
+    assert_eq!(
+        unsafe { SifiveClint::<1>::try_from_base_addr(base + 8) }.unwrap_err(),
+        AlignmentError::Misaligned {
+            base: base + 8,
+            align: 0x4000
+        }
+    );
+    assert_eq!(
+        unsafe { SifiveClint::<1>::try_from_base_addr(0) }.unwrap_err(),
+        AlignmentError::Null
+    );
+}
+
+#[test]
+fn test_send_and_wait_ack_timeout() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    assert_eq!(clint.send_and_wait_ack(1, 0), Err(TimeoutError));
+    assert!(clint.read_msip(1));
+}
+
+#[test]
+fn test_advance_mtimecmp() {
+    let clint = unsafe { SifiveClint::<1>::new_boxed() };
+    clint.write_mtimecmp(0, 7);
+    for i in 1..=1000 {
+        clint.write_mtime(7 + i * 10 + i % 3);
+        clint.advance_mtimecmp(0, 10);
+        assert_eq!(clint.read_mtimecmp(0), 7 + i * 10);
+    }
+}
+
+#[test]
+fn test_disable_all_timers() {
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    clint.disable_all_timers(3);
+    assert!(clint.mtimecmp_iter(0..4).eq([NEVER, NEVER, NEVER, 0]));
+}
+
+#[test]
+fn test_broadcast_ipi_and_wait() {
+    use core::sync::atomic::AtomicUsize;
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    let ack = AtomicUsize::new(2);
+    assert_eq!(
+        clint.broadcast_ipi_and_wait([1, 3].into_iter(), &ack, 0),
+        Ok(())
+    );
+    assert!(clint.pending_msips(0..4).eq([1, 3]));
+    assert_eq!(
+        clint.broadcast_ipi_and_wait(0..3, &ack, 0),
+        Err(TimeoutError)
+    );
+}
+
+#[test]
+fn test_self_check() {
+    let clint = unsafe { SifiveClint4095::new_boxed() };
+    clint.write_mtimecmp(0, 42);
+    clint.set_msip(0);
+    assert_eq!(clint.self_check(), Ok(()));
+    assert_eq!(clint.read_mtimecmp(0), 42);
+    assert!(clint.read_msip(0));
+
+    let base = (&clint.mswi as *const MSWI4095).cast::<u8>();
+    unsafe { (base.add(MSWI4095::RESERVED_OFFSET) as *mut u32).write_volatile(1) };
+    assert_eq!(clint.self_check(), Err(SelfCheckError::ReservedNonZero(1)));
+}
+
+#[test]
+fn test_send_ipi_where() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    clint.send_ipi_where(0..16, |i| i % 2 == 1);
+    assert!(clint.pending_msips(0..8).eq([1, 3, 5, 7]));
+}
+
+#[cfg(feature = "strict-bounds")]
+#[test]
+#[should_panic(expected = "hart 8 out of range, device has 8 harts")]
+fn test_strict_bounds() {
+    unsafe { SifiveClint::<8>::new_boxed() }.set_msip(8);
+}
+
+#[test]
+fn test_ticks_until_deadline() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    clint.write_mtime(100);
+    clint.write_mtimecmp(0, 150);
+    clint.write_mtimecmp(1, 100);
+    assert_eq!(clint.ticks_until_deadline(0), Some(50));
+    assert_eq!(clint.ticks_until_deadline(1), None);
+}
+
+#[test]
+fn test_sync() {
+    fn assert_sync<T: Sync>() {}
+    assert_sync::<MTIMER4095>();
+    assert_sync::<MSWI4095>();
+    assert_sync::<SSWI4095>();
+    assert_sync::<SifiveClint4095>();
+    assert_sync::<Rv32Mtimer4095>();
+}
+
+#[test]
+fn test_write_mtimecmp_rv32_safe() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    clint.write_mtimecmp_rv32_safe(3, 0x1234_5678_9abc_def0);
+    assert_eq!(clint.read_mtimecmp(3), 0x1234_5678_9abc_def0);
+    assert_eq!(clint.read_mtimecmp(2), 0);
+}
+
+#[test]
+fn test_measure_read_overhead() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    clint.write_mtime(100);
+    assert_eq!(clint.measure_read_overhead(0), 0);
+    assert_eq!(clint.measure_read_overhead(16), 0);
+}
+
+#[test]
+fn test_clear_all_msip() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    clint.send_ipi_where(0..8, |_| true);
+    clint.clear_all_msip(5);
+    assert!(clint.pending_msips(0..8).eq([5, 6, 7]));
 }
@@ -0,0 +1,217 @@
+use crate::{
+    volatile::{ReadLe, WriteLe},
+    SifiveClint,
+};
+
+/// Performs the register accesses of a [`ClintOver`],
+/// e.g. to trace, record and replay, or inject faults into them.
+///
+/// [`ClintOver`] only calls the methods with addresses of registers inside the CLINT
+/// given to [`ClintOver::new`], aligned to the access size.
+pub trait MmioAccess {
+    /// Reads the 32-bit register at `addr`.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be a register address valid for this accessor.
+    unsafe fn read_u32(&self, addr: usize) -> u32;
+
+    /// Writes `val` to the 32-bit register at `addr`.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be a register address valid for this accessor.
+    unsafe fn write_u32(&self, addr: usize, val: u32);
+
+    /// Reads the 64-bit register at `addr`.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be a register address valid for this accessor.
+    unsafe fn read_u64(&self, addr: usize) -> u64;
+
+    /// Writes `val` to the 64-bit register at `addr`.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be a register address valid for this accessor.
+    unsafe fn write_u64(&self, addr: usize, val: u64);
+}
+
+/// Accesses the registers with little-endian volatile loads and stores,
+/// exactly as [`SifiveClint`] does.
+///
+/// Being zero-sized and monomorphized, it adds no cost over [`SifiveClint`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DirectMmio;
+
+impl MmioAccess for DirectMmio {
+    #[inline(always)]
+    unsafe fn read_u32(&self, addr: usize) -> u32 {
+        (addr as *const u32).read_le()
+    }
+
+    #[inline(always)]
+    unsafe fn write_u32(&self, addr: usize, val: u32) {
+        (addr as *mut u32).write_le(val)
+    }
+
+    #[inline(always)]
+    unsafe fn read_u64(&self, addr: usize) -> u64 {
+        (addr as *const u64).read_le()
+    }
+
+    #[inline(always)]
+    unsafe fn write_u64(&self, addr: usize, val: u64) {
+        (addr as *mut u64).write_le(val)
+    }
+}
+
+/// A SiFive CLINT with `H` harts at `base`, whose registers are accessed through `A`.
+pub struct ClintOver<A, const H: usize> {
+    base: usize,
+    access: A,
+}
+
+impl<A: MmioAccess, const H: usize> ClintOver<A, H> {
+    /// Creates a CLINT at `base` accessed through `access`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be valid for `access` as the base of a [`SifiveClint<H>`] for as long as
+    /// the returned value lives, e.g. a mapped CLINT for [`DirectMmio`].
+    #[inline]
+    pub const unsafe fn new(base: usize, access: A) -> Self {
+        Self { base, access }
+    }
+
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+
+    #[inline]
+    pub const fn access(&self) -> &A {
+        &self.access
+    }
+
+    #[inline]
+    pub fn read_mtime(&self) -> u64 {
+        unsafe {
+            self.access
+                .read_u64(self.base + SifiveClint::<H>::mtime_offset())
+        }
+    }
+
+    #[inline]
+    pub fn write_mtime(&self, val: u64) {
+        unsafe {
+            self.access
+                .write_u64(self.base + SifiveClint::<H>::mtime_offset(), val)
+        }
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+        check(hart_idx, H);
+        unsafe {
+            self.access
+                .read_u64(self.base + SifiveClint::<H>::mtimecmp_offset(hart_idx))
+        }
+    }
+
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+        check(hart_idx, H);
+        unsafe {
+            self.access
+                .write_u64(self.base + SifiveClint::<H>::mtimecmp_offset(hart_idx), val)
+        }
+    }
+
+    #[inline]
+    pub fn read_msip(&self, hart_idx: usize) -> bool {
+        check(hart_idx, H);
+        unsafe {
+            self.access
+                .read_u32(self.base + SifiveClint::<H>::msip_offset(hart_idx))
+                != 0
+        }
+    }
+
+    #[inline]
+    pub fn set_msip(&self, hart_idx: usize) {
+        check(hart_idx, H);
+        unsafe {
+            self.access
+                .write_u32(self.base + SifiveClint::<H>::msip_offset(hart_idx), 1)
+        }
+    }
+
+    #[inline]
+    pub fn clear_msip(&self, hart_idx: usize) {
+        check(hart_idx, H);
+        unsafe {
+            self.access
+                .write_u32(self.base + SifiveClint::<H>::msip_offset(hart_idx), 0)
+        }
+    }
+}
+
+/// Unlike the register accessors, this is not a slice bounds check and so is never skipped:
+/// an out-of-range hart would otherwise address outside the device.
+#[inline]
+fn check(hart_idx: usize, max: usize) {
+    assert!(
+        hart_idx < max,
+        "hart {hart_idx} out of range, device has {max} harts"
+    );
+}
+
+#[test]
+fn test_clint_over() {
+    use core::cell::RefCell;
+    use std::vec::Vec;
+
+    struct Tracing(RefCell<Vec<(bool, usize, u64)>>);
+
+    impl MmioAccess for Tracing {
+        unsafe fn read_u32(&self, addr: usize) -> u32 {
+            let val = DirectMmio.read_u32(addr);
+            self.0.borrow_mut().push((false, addr, val as _));
+            val
+        }
+
+        unsafe fn write_u32(&self, addr: usize, val: u32) {
+            self.0.borrow_mut().push((true, addr, val as _));
+            DirectMmio.write_u32(addr, val)
+        }
+
+        unsafe fn read_u64(&self, addr: usize) -> u64 {
+            let val = DirectMmio.read_u64(addr);
+            self.0.borrow_mut().push((false, addr, val));
+            val
+        }
+
+        unsafe fn write_u64(&self, addr: usize, val: u64) {
+            self.0.borrow_mut().push((true, addr, val));
+            DirectMmio.write_u64(addr, val)
+        }
+    }
+
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    let base = &*clint as *const SifiveClint<8> as usize;
+    let over = unsafe { ClintOver::<_, 8>::new(base, Tracing(RefCell::new(Vec::new()))) };
+    over.write_mtimecmp(1, 42);
+    over.set_msip(2);
+    assert!(over.read_msip(2));
+    assert_eq!(clint.read_mtimecmp(1), 42);
+    assert_eq!(
+        *over.access().0.borrow(),
+        [
+            (true, base + 0x4008, 42),
+            (true, base + 0x8, 1),
+            (false, base + 0x8, 1)
+        ]
+    );
+}
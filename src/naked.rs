@@ -0,0 +1,197 @@
+//! None of the naked functions check `hart_idx`, it must be below the device capacity,
+//! which never exceeds [`MAX_HARTS`](crate::MAX_HARTS).
+//!
+//! The registers are little-endian and the values are passed through as loaded,
+//! so these functions are only correct on little-endian harts.
+//!
+//! The functions accessing the 64-bit timer registers use `ld` and `sd`,
+//! so they exist on RV64 only.
+
+#[cfg(target_arch = "riscv64")]
+use crate::NEVER;
+use crate::{SifiveClint, SSWI};
+use core::arch::naked_asm;
+
+impl<const H: usize> SifiveClint<H> {
+    #[cfg(target_arch = "riscv64")]
+    #[unsafe(naked)]
+    pub extern "C" fn read_mtime_naked(&self) -> u64 {
+        naked_asm!(
+            "   addi sp, sp, -8
+                sd   a1, (sp)
+
+                li   a1, {offset}
+                add  a0, a0, a1
+
+                ld   a1, (sp)
+                addi sp, sp,  8
+
+                ld   a0, (a0)
+                ret
+            ",
+            offset = const Self::MTIME_OFFSET,
+        )
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    #[unsafe(naked)]
+    pub extern "C" fn write_mtime_naked(&self, val: u64) -> u64 {
+        naked_asm!(
+            "   addi sp, sp, -8
+                sd   a1, (sp)
+
+                li   a1, {offset}
+                add  a0, a0, a1
+
+                ld   a1, (sp)
+                addi sp, sp,  8
+
+                sd   a1, (a0)
+                ret
+            ",
+            offset = const Self::MTIME_OFFSET,
+        )
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    #[unsafe(naked)]
+    pub extern "C" fn read_mtimecmp_naked(&self, hart_idx: usize) -> u64 {
+        naked_asm!(
+            "   slli a1, a1, 3
+                add  a0, a0, a1
+
+                li   a1, {offset}
+                add  a0, a0, a1
+
+                ld   a0, (a0)
+                ret
+            ",
+            offset = const Self::MTIMER_OFFSET,
+        )
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    #[unsafe(naked)]
+    pub extern "C" fn write_mtimecmp_naked(&self, hart_idx: usize, val: u64) {
+        naked_asm!(
+            "   slli a1, a1, 3
+                add  a0, a0, a1
+
+                li   a1, {offset}
+                add  a0, a0, a1
+
+                sd   a2, (a0)
+                ret
+            ",
+            offset = const Self::MTIMER_OFFSET,
+        )
+    }
+
+    /// Sets `mtimecmp` of `hart_idx` to `mtime + delta`.
+    ///
+    /// The addition wraps like the hardware adder, unlike
+    /// [`set_mtimecmp_after`](SifiveClint::set_mtimecmp_after) which saturates.
+    #[cfg(target_arch = "riscv64")]
+    #[unsafe(naked)]
+    pub extern "C" fn set_mtimecmp_after_naked(&self, hart_idx: usize, delta: u64) {
+        naked_asm!(
+            "   li   a3, {mtime}
+                add  a3, a0, a3
+                ld   a3, (a3)
+                add  a2, a2, a3
+
+                slli a1, a1, 3
+                add  a0, a0, a1
+
+                li   a1, {mtimer}
+                add  a0, a0, a1
+
+                sd   a2, (a0)
+                ret
+            ",
+            mtime  = const Self::MTIME_OFFSET,
+            mtimer = const Self::MTIMER_OFFSET,
+        )
+    }
+
+    #[cfg(target_arch = "riscv64")]
+    #[unsafe(naked)]
+    pub extern "C" fn clear_mtimecmp_naked(&self, hart_idx: usize) {
+        naked_asm!(
+            "   slli a1, a1, 3
+                add  a0, a0, a1
+
+                li   a1, {offset}
+                add  a0, a0, a1
+
+                li   a1, {never}
+                sd   a1, (a0)
+                ret
+            ",
+            offset = const Self::MTIMER_OFFSET,
+            never  = const NEVER as i64,
+        )
+    }
+
+    #[unsafe(naked)]
+    pub extern "C" fn read_msip_naked(&self, hart_idx: usize) -> bool {
+        naked_asm!(
+            "   slli a1, a1, 2
+                add  a0, a0, a1
+                lw   a0, (a0)
+                ret
+            ",
+        )
+    }
+
+    #[unsafe(naked)]
+    pub extern "C" fn set_msip_naked(&self, hart_idx: usize) {
+        naked_asm!(
+            "   slli a1, a1, 2
+                add  a0, a0, a1
+                addi a1, zero, 1
+                sw   a1, (a0)
+                ret
+            ",
+        )
+    }
+
+    #[unsafe(naked)]
+    pub extern "C" fn clear_msip_naked(&self, hart_idx: usize) {
+        naked_asm!(
+            "   slli a1, a1, 2
+                add  a0, a0, a1
+                sw   zero, (a0)
+                ret
+            ",
+        )
+    }
+}
+
+impl<const N: usize> SSWI<N> {
+    #[unsafe(naked)]
+    pub extern "C" fn read_setssip_naked(&self, hart_idx: usize) -> bool {
+        naked_asm!(
+            "   slli a1, a1, 2
+                add  a0, a0, a1
+                lw   a0, (a0)
+                ret
+            ",
+        )
+    }
+
+    /// Naked counterpart of [`set_setssip`](SSWI::set_setssip), usable from a trap handler without a stack.
+    ///
+    /// `SETSSIP` is write-1-to-set, so there is intentionally no `clear_setssip_naked`.
+    #[unsafe(naked)]
+    pub extern "C" fn set_setssip_naked(&self, hart_idx: usize) {
+        naked_asm!(
+            "   slli a1, a1, 2
+                add  a0, a0, a1
+                addi a1, zero, 1
+                sw   a1, (a0)
+                ret
+            ",
+        )
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{DirectMmio, MmioAccess, SifiveClint4095, MSWI4095, MTIMER4095, SSWI4095};
+use core::hint::spin_loop;
+
+/// Kinds of CLINT and ACLINT devices this crate can operate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AclintDevice {
+    /// ACLINT machine-level software interrupt device.
+    Mswi,
+    /// ACLINT supervisor-level software interrupt device.
+    Sswi,
+    /// ACLINT machine-level timer device.
+    Mtimer,
+    /// SiFive CLINT, i.e. an MSWI followed by an MTIMER.
+    SifiveClint,
+}
+
+impl AclintDevice {
+    /// Maps a device-tree `compatible` string to the device kind.
+    pub fn from_compatible(compatible: &str) -> Option<Self> {
+        match compatible {
+            "riscv,aclint-mswi" => Some(Self::Mswi),
+            "riscv,aclint-sswi" => Some(Self::Sswi),
+            "riscv,aclint-mtimer" => Some(Self::Mtimer),
+            "sifive,clint0" | "riscv,clint0" => Some(Self::SifiveClint),
+            _ => None,
+        }
+    }
+}
+
+/// A typed handle returned by [`probe`].
+#[derive(Clone, Copy)]
+pub enum AclintHandle {
+    Mswi(&'static MSWI4095),
+    Sswi(&'static SSWI4095),
+    Mtimer(&'static MTIMER4095),
+    SifiveClint(&'static SifiveClint4095),
+}
+
+/// Constructs the accessor of a `kind` device mapped at `base`.
+///
+/// # Safety
+///
+/// See `from_base_addr` of the device type selected by `kind`.
+pub unsafe fn probe(base: usize, kind: AclintDevice) -> AclintHandle {
+    match kind {
+        AclintDevice::Mswi => AclintHandle::Mswi(MSWI4095::from_base_addr(base)),
+        AclintDevice::Sswi => AclintHandle::Sswi(SSWI4095::from_base_addr(base)),
+        AclintDevice::Mtimer => AclintHandle::Mtimer(MTIMER4095::from_base_addr(base)),
+        AclintDevice::SifiveClint => {
+            AclintHandle::SifiveClint(SifiveClint4095::from_base_addr(base))
+        }
+    }
+}
+
+/// Register layouts [`detect_layout_by_mtime`] can tell apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClintLayout {
+    /// A SiFive CLINT, with MTIMER right after the MSWI.
+    SifiveCombined,
+    /// A split ACLINT, with only the MSWI known to be at the probed base.
+    Aclint,
+}
+
+/// Best-effort guess of the layout of the device whose MSWI is mapped at `base`,
+/// by watching where a SiFive CLINT keeps `mtime`.
+///
+/// This is a heuristic performing two checks:
+///
+/// 1. The reserved word at `base + 0x3ffc` must read as zero, otherwise `base`
+///    is not the start of an MSWI and `None` is returned.
+/// 2. The word at `base + 0xbff8`, inside the timer window a SiFive CLINT has after the MSWI,
+///    is read up to `max_reads` times, and at least once.
+///    If it changes, a running `mtime` is assumed and the layout is [`ClintLayout::SifiveCombined`],
+///    otherwise it is [`ClintLayout::Aclint`].
+///
+/// A split ACLINT whose MTIMER happens to sit right after the MSWI is reported as combined,
+/// which is harmless since the register maps are then identical.
+/// Both checks only read and have no side effects,
+/// but `max_reads` should cover a few ticks of the timebase for a running `mtime` to be seen.
+///
+/// # Safety
+///
+/// The whole 48 KiB window starting at `base` must be readable,
+/// including the 32 KiB after the MSWI that only a SiFive CLINT is known to map.
+pub unsafe fn detect_layout_by_mtime(base: usize, max_reads: usize) -> Option<ClintLayout> {
+    detect_layout_over(&DirectMmio, base, max_reads)
+}
+
+unsafe fn detect_layout_over<A: MmioAccess>(
+    access: &A,
+    base: usize,
+    max_reads: usize,
+) -> Option<ClintLayout> {
+    if access.read_u32(base + MSWI4095::RESERVED_OFFSET) != 0 {
+        return None;
+    }
+    let mtime = base + SifiveClint4095::MTIME_OFFSET;
+    let first = access.read_u64(mtime);
+    for _ in 1..max_reads {
+        if access.read_u64(mtime) != first {
+            return Some(ClintLayout::SifiveCombined);
+        }
+        spin_loop();
+    }
+    Some(ClintLayout::Aclint)
+}
+
+#[cfg(test)]
+struct ProbeMock {
+    reserved: u32,
+    ticking: bool,
+    mtime_reads: core::cell::Cell<u64>,
+}
+
+#[cfg(test)]
+impl MmioAccess for ProbeMock {
+    unsafe fn read_u32(&self, addr: usize) -> u32 {
+        assert_eq!(addr, MSWI4095::RESERVED_OFFSET);
+        self.reserved
+    }
+
+    unsafe fn write_u32(&self, _: usize, _: u32) {
+        unreachable!()
+    }
+
+    unsafe fn read_u64(&self, addr: usize) -> u64 {
+        assert_eq!(addr, SifiveClint4095::MTIME_OFFSET);
+        let reads = self.mtime_reads.get() + 1;
+        self.mtime_reads.set(reads);
+        if self.ticking {
+            reads / 4
+        } else {
+            0
+        }
+    }
+
+    unsafe fn write_u64(&self, _: usize, _: u64) {
+        unreachable!()
+    }
+}
+
+#[test]
+fn test_detect_layout() {
+    let mock = |reserved, ticking| ProbeMock {
+        reserved,
+        ticking,
+        mtime_reads: core::cell::Cell::new(0),
+    };
+    let sifive = mock(0, true);
+    assert_eq!(
+        unsafe { detect_layout_over(&sifive, 0, 64) },
+        Some(ClintLayout::SifiveCombined)
+    );
+    assert_eq!(sifive.mtime_reads.get(), 4);
+
+    let aclint = mock(0, false);
+    assert_eq!(
+        unsafe { detect_layout_over(&aclint, 0, 64) },
+        Some(ClintLayout::Aclint)
+    );
+    assert_eq!(aclint.mtime_reads.get(), 64);
+
+    let not_mswi = mock(1, true);
+    assert_eq!(unsafe { detect_layout_over(&not_mswi, 0, 64) }, None);
+    assert_eq!(not_mswi.mtime_reads.get(), 0);
+}
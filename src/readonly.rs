@@ -0,0 +1,66 @@
+use crate::{SifiveClint, MTIMER};
+
+/// An MTIMER whose `mtime` can be read but not written.
+///
+/// `mtime` is global, and on many platforms only one security context may write it;
+/// a stray [`MTIMER::write_mtime`] from another hart corrupts time for everyone.
+/// Handing this view to everything but the designated timekeeper turns such writes
+/// into compile errors, while per-hart `mtimecmp` programming stays available.
+/// A type rather than a cargo feature, since features must not remove API.
+#[derive(Clone, Copy)]
+pub struct ReadOnlyMtime<'a, const N: usize>(&'a MTIMER<N>);
+
+impl<const N: usize> MTIMER<N> {
+    /// Returns a view of this MTIMER that cannot write `mtime`.
+    #[inline]
+    pub fn read_only(&self) -> ReadOnlyMtime<'_, N> {
+        ReadOnlyMtime(self)
+    }
+}
+
+impl<const H: usize> SifiveClint<H> {
+    /// See [`MTIMER::read_only`].
+    #[inline]
+    pub fn read_only_mtime(&self) -> ReadOnlyMtime<'_, H> {
+        self.mtimer.read_only()
+    }
+}
+
+impl<const N: usize> ReadOnlyMtime<'_, N> {
+    #[inline]
+    pub fn read_mtime(&self) -> u64 {
+        self.0.read_mtime()
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+        self.0.read_mtimecmp(hart_idx)
+    }
+
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+        self.0.write_mtimecmp(hart_idx, val)
+    }
+
+    /// See [`MTIMER::set_mtimecmp_after`].
+    #[inline]
+    pub fn set_mtimecmp_after(&self, hart_idx: usize, delta: u64) {
+        self.0.set_mtimecmp_after(hart_idx, delta)
+    }
+
+    /// See [`MTIMER::clear_mtimecmp`].
+    #[inline]
+    pub fn clear_mtimecmp(&self, hart_idx: usize) {
+        self.0.clear_mtimecmp(hart_idx)
+    }
+}
+
+#[test]
+fn test_read_only_mtime() {
+    let clint = unsafe { SifiveClint::<2>::new_boxed() };
+    clint.write_mtime(40);
+    let mtime = clint.read_only_mtime();
+    assert_eq!(mtime.read_mtime(), 40);
+    mtime.set_mtimecmp_after(1, 2);
+    assert_eq!(clint.read_mtimecmp(1), 42);
+}
@@ -0,0 +1,111 @@
+use crate::{SifiveClint, SifiveClint4095, MSIP, MSWI4095, MTIMECMP};
+use core::mem::size_of;
+
+/// Identity of a register in a [`SifiveClint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClintReg {
+    /// `msip` of a hart.
+    Msip(usize),
+    /// `mtimecmp` of a hart.
+    Mtimecmp(usize),
+    /// `mtime`.
+    Mtime,
+    /// Padding after the implemented `msip`s, including the reserved word of MSWI,
+    /// or after the implemented `mtimecmp`s.
+    Reserved,
+}
+
+impl ClintReg {
+    /// Byte offset of the register from the CLINT base, the inverse of
+    /// [`SifiveClint::classify_address`].
+    ///
+    /// The layout does not depend on the hart count,
+    /// and [`Reserved`](Self::Reserved) maps to the reserved word of MSWI.
+    #[inline]
+    pub const fn byte_offset(&self) -> usize {
+        match *self {
+            Self::Msip(hart_idx) => SifiveClint4095::msip_offset(hart_idx),
+            Self::Mtimecmp(hart_idx) => SifiveClint4095::mtimecmp_offset(hart_idx),
+            Self::Mtime => SifiveClint4095::mtime_offset(),
+            Self::Reserved => MSWI4095::RESERVED_OFFSET,
+        }
+    }
+}
+
+impl<const H: usize> SifiveClint<H> {
+    /// Finds which register `addr` falls into, for logging or emulating faulting accesses.
+    ///
+    /// Returns `None` if `addr` is outside this CLINT.
+    pub fn classify_address(&self, addr: usize) -> Option<ClintReg> {
+        let offset = addr.checked_sub(self as *const Self as usize)?;
+        if offset < H * size_of::<MSIP>() {
+            Some(ClintReg::Msip(offset / size_of::<MSIP>()))
+        } else if offset < Self::MTIMER_OFFSET {
+            Some(ClintReg::Reserved)
+        } else if offset < Self::MTIMER_OFFSET + H * size_of::<MTIMECMP>() {
+            Some(ClintReg::Mtimecmp(
+                (offset - Self::MTIMER_OFFSET) / size_of::<MTIMECMP>(),
+            ))
+        } else if offset < Self::MTIME_OFFSET {
+            Some(ClintReg::Reserved)
+        } else if offset < Self::MTIME_OFFSET + size_of::<u64>() {
+            Some(ClintReg::Mtime)
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn test_classify_address() {
+    let clint = unsafe { SifiveClint::<4095>::new_boxed() };
+    let base = &*clint as *const SifiveClint<4095> as usize;
+    assert_eq!(clint.classify_address(base + 0x8), Some(ClintReg::Msip(2)));
+    assert_eq!(
+        clint.classify_address(base + 0x3ffc),
+        Some(ClintReg::Reserved)
+    );
+    assert_eq!(
+        clint.classify_address(base + 0x400c),
+        Some(ClintReg::Mtimecmp(1))
+    );
+    assert_eq!(clint.classify_address(base + 0xbffc), Some(ClintReg::Mtime));
+    assert_eq!(clint.classify_address(base + 0xc000), None);
+    assert_eq!(clint.classify_address(base - 1), None);
+}
+
+#[test]
+fn test_byte_offset() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    let base = &*clint as *const SifiveClint<8> as usize;
+    for reg in [
+        ClintReg::Msip(0),
+        ClintReg::Msip(7),
+        ClintReg::Reserved,
+        ClintReg::Mtimecmp(0),
+        ClintReg::Mtimecmp(7),
+        ClintReg::Mtime,
+    ] {
+        assert_eq!(clint.classify_address(base + reg.byte_offset()), Some(reg));
+    }
+}
+
+#[test]
+fn test_classify_address_gap() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    let base = &*clint as *const SifiveClint<8> as usize;
+    assert_eq!(
+        clint.classify_address(base + 0x4038),
+        Some(ClintReg::Mtimecmp(7))
+    );
+    assert_eq!(
+        clint.classify_address(base + 0x4040),
+        Some(ClintReg::Reserved)
+    );
+    assert_eq!(
+        clint.classify_address(base + 0xbff0),
+        Some(ClintReg::Reserved)
+    );
+    assert_eq!(clint.classify_address(base + 0xbff8), Some(ClintReg::Mtime));
+}
@@ -0,0 +1,134 @@
+use crate::{
+    check_hart,
+    volatile::{ReadLe, WriteLe},
+    MtimecmpArray, MAX_HARTS,
+};
+use core::{cell::UnsafeCell, mem::size_of};
+
+/// A 64-bit timer register exposed as two 32-bit registers.
+#[repr(C)]
+pub struct Rv32Reg {
+    lo: UnsafeCell<u32>,
+    hi: UnsafeCell<u32>,
+}
+
+// SAFETY: the halves are only accessed through volatile MMIO, see the `Sync` impls of the
+// 64-bit registers in the crate root.
+unsafe impl Sync for Rv32Reg {}
+
+/// An MTIMER on RV32-only implementations, where the 64-bit registers
+/// can only be accessed as 32-bit halves.
+#[repr(C)]
+pub struct Rv32Mtimer<const N: usize> {
+    pub mtimecmp: MtimecmpArray<Rv32Reg, N>,
+    pub mtime: Rv32Reg,
+}
+
+pub type Rv32Mtimer4095 = Rv32Mtimer<MAX_HARTS>;
+
+const _: () = assert!(size_of::<Rv32Mtimer4095>() == 0x8000);
+const _: () = assert!(size_of::<Rv32Mtimer<2>>() == 0x8000);
+
+impl<const N: usize> Rv32Mtimer<N> {
+    /// Returns a reference to the MTIMER mapped at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be 8-byte aligned, as for [`MTIMER`](crate::MTIMER), even though only 32-bit
+    /// accesses are made, and the whole `size_of::<Self>()`-byte region starting at it
+    /// must be a valid MTIMER MMIO window for the rest of the program.
+    #[inline]
+    pub const unsafe fn from_base_addr(base: usize) -> &'static Self {
+        &*(base as *const Self)
+    }
+
+    /// Reads `mtime` high, low, then high again, retrying if the high half changed.
+    #[inline]
+    pub fn read_mtime(&self) -> u64 {
+        loop {
+            let hi = unsafe { self.mtime.hi.get().read_le() };
+            let lo = unsafe { self.mtime.lo.get().read_le() };
+            if hi == unsafe { self.mtime.hi.get().read_le() } {
+                break ((hi as u64) << 32) | lo as u64;
+            }
+        }
+    }
+
+    /// Writes `mtime` as low zero, high, then low, so no carry happens between the halves.
+    #[inline]
+    pub fn write_mtime(&self, val: u64) {
+        unsafe {
+            self.mtime.lo.get().write_le(0);
+            self.mtime.hi.get().write_le((val >> 32) as u32);
+            self.mtime.lo.get().write_le(val as u32);
+        }
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+        check_hart::<N>(hart_idx);
+        let reg = &self.mtimecmp[hart_idx];
+        let hi = unsafe { reg.hi.get().read_le() };
+        let lo = unsafe { reg.lo.get().read_le() };
+        ((hi as u64) << 32) | lo as u64
+    }
+
+    /// Writes `mtimecmp` of `hart_idx` as high `u32::MAX`, low, then high.
+    ///
+    /// Writing the high half to `u32::MAX` first keeps every intermediate compare value
+    /// in the future, so no spurious timer interrupt fires between the stores.
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+        check_hart::<N>(hart_idx);
+        let reg = &self.mtimecmp[hart_idx];
+        for (half, word) in split_mtimecmp_write(val) {
+            let ptr = match half {
+                Half::Lo => reg.lo.get(),
+                Half::Hi => reg.hi.get(),
+            };
+            unsafe { ptr.write_le(word) };
+        }
+    }
+}
+
+/// Half of a 64-bit register accessed as two 32-bit registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Half {
+    Lo,
+    Hi,
+}
+
+/// The 32-bit stores that write `val` to a compare register: high to `u32::MAX`, low, then high.
+///
+/// Writing the high half to `u32::MAX` first keeps every intermediate compare value
+/// in the future, so no spurious timer interrupt fires between the stores.
+#[inline]
+pub(crate) const fn split_mtimecmp_write(val: u64) -> [(Half, u32); 3] {
+    [
+        (Half::Hi, u32::MAX),
+        (Half::Lo, val as u32),
+        (Half::Hi, (val >> 32) as u32),
+    ]
+}
+
+#[test]
+fn test_rv32_mtimer() {
+    let mtimer = unsafe { Rv32Mtimer::<2>::new_boxed() };
+    mtimer.write_mtime(0x1_2345_6789);
+    assert_eq!(mtimer.read_mtime(), 0x1_2345_6789);
+    mtimer.write_mtimecmp(1, 0xfedc_ba98_7654_3210);
+    assert_eq!(mtimer.read_mtimecmp(1), 0xfedc_ba98_7654_3210);
+    assert_eq!(mtimer.read_mtimecmp(0), 0);
+}
+
+#[test]
+fn test_split_mtimecmp_write() {
+    assert_eq!(
+        split_mtimecmp_write(0x1234_5678_9abc_def0),
+        [
+            (Half::Hi, u32::MAX),
+            (Half::Lo, 0x9abc_def0),
+            (Half::Hi, 0x1234_5678),
+        ]
+    );
+}
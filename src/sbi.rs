@@ -0,0 +1,72 @@
+use crate::SifiveClint;
+use rustsbi::{spec::binary::SbiRet, HartMask, Ipi, Timer};
+
+/// Implements [`rustsbi::Timer`] on a [`SifiveClint`].
+///
+/// `hart_id` returns the index of the current hart in the CLINT.
+pub struct TimerClint<'a, F, const H: usize> {
+    clint: &'a SifiveClint<H>,
+    hart_id: F,
+}
+
+impl<'a, F, const H: usize> TimerClint<'a, F, H> {
+    #[inline]
+    pub const fn new(clint: &'a SifiveClint<H>, hart_id: F) -> Self {
+        Self { clint, hart_id }
+    }
+}
+
+impl<F, const H: usize> Timer for TimerClint<'_, F, H>
+where
+    F: Fn() -> usize + Send + Sync,
+{
+    #[inline]
+    fn set_timer(&self, stime_value: u64) {
+        self.clint.write_mtimecmp((self.hart_id)(), stime_value)
+    }
+}
+
+/// Implements [`rustsbi::Ipi`] on a [`SifiveClint`].
+///
+/// `allow_ipi` is called with every hart index selected by the mask,
+/// and the MSIP of the hart is set only if it returns `true`.
+/// [`send_ipi`](Ipi::send_ipi) returns `SbiRet::success(0)` after setting the relevant MSIPs.
+pub struct IpiClint<'a, F, const H: usize> {
+    clint: &'a SifiveClint<H>,
+    allow_ipi: F,
+}
+
+impl<'a, F, const H: usize> IpiClint<'a, F, H> {
+    #[inline]
+    pub const fn new(clint: &'a SifiveClint<H>, allow_ipi: F) -> Self {
+        Self { clint, allow_ipi }
+    }
+}
+
+impl<F, const H: usize> Ipi for IpiClint<'_, F, H>
+where
+    F: Fn(usize) -> bool + Send + Sync,
+{
+    fn send_ipi(&self, hart_mask: HartMask) -> SbiRet {
+        self.clint
+            .send_ipi_where(0..H, |i| hart_mask.has_bit(i) && (self.allow_ipi)(i));
+        SbiRet::success(0)
+    }
+}
+
+#[test]
+fn test_timer() {
+    fn is_timer<T: Timer>() {}
+    is_timer::<TimerClint<fn() -> usize, { crate::MAX_HARTS }>>();
+}
+
+#[test]
+fn test_ipi() {
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    let ipi = IpiClint::new(&*clint, |i| i != 2);
+    let ret = ipi.send_ipi(HartMask::from_mask_base(0b101, 0));
+    assert_eq!(ret.error, SbiRet::success(0).error);
+    assert!(clint.read_msip(0));
+    assert!(!clint.read_msip(1));
+    assert!(!clint.read_msip(2));
+}
@@ -0,0 +1,246 @@
+//! Simulation-only device models.
+//!
+//! These types do **not** match any hardware register map and must never be mapped onto MMIO.
+
+use crate::{
+    volatile::{ReadLe, WriteLe},
+    MSIP,
+};
+use core::{
+    cell::{Cell, UnsafeCell},
+    marker::PhantomData,
+};
+
+/// An `msip` register alone on its 64-byte cache line.
+#[repr(C, align(64))]
+pub struct PaddedMSIP(MSIP);
+
+/// An MSWI whose `msip`s are cache-line aligned, so harts signaling each other
+/// in a software model do not falsely share cache lines.
+///
+/// It lives in ordinary memory, where concurrent accesses race, so it is not `Sync`.
+#[repr(transparent)]
+pub struct PaddedMSWI<const N: usize>([PaddedMSIP; N], PhantomData<Cell<()>>);
+
+impl<const N: usize> PaddedMSWI<N> {
+    /// Creates a device with every `msip` clear.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(
+            [const { PaddedMSIP(MSIP(UnsafeCell::new(0))) }; N],
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    pub fn read_msip(&self, hart_idx: usize) -> bool {
+        unsafe { self.0[hart_idx].0 .0.get().read_le() != 0 }
+    }
+
+    #[inline]
+    pub fn set_msip(&self, hart_idx: usize) {
+        unsafe { self.0[hart_idx].0 .0.get().write_le(1) }
+    }
+
+    #[inline]
+    pub fn clear_msip(&self, hart_idx: usize) {
+        unsafe { self.0[hart_idx].0 .0.get().write_le(0) }
+    }
+}
+
+impl<const N: usize> Default for PaddedMSWI<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_padded_mswi() {
+    assert_eq!(core::mem::size_of::<PaddedMSWI<4>>(), 4 * 64);
+    let mswi = PaddedMSWI::<4>::new();
+    mswi.set_msip(1);
+    assert!(mswi.read_msip(1));
+    assert!(!mswi.read_msip(0));
+    mswi.clear_msip(1);
+    assert!(!mswi.read_msip(1));
+}
+
+#[cfg(feature = "std")]
+pub use running::{SimMtime, SimMtimer};
+
+#[cfg(feature = "std")]
+mod running {
+    use crate::{MtimerOps, SifiveClint, NEVER};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+        vec::Vec,
+    };
+
+    /// A shareable `mtime` backed by an `AtomicU64`, optionally advanced by a background thread.
+    pub struct SimMtime {
+        ticks: Arc<AtomicU64>,
+        running: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl SimMtime {
+        /// Creates a stopped counter at zero, only advanced by [`advance`](Self::advance)
+        /// and [`write_mtime`](Self::write_mtime).
+        pub fn new() -> Self {
+            Self {
+                ticks: Arc::new(AtomicU64::new(0)),
+                running: Arc::new(AtomicBool::new(false)),
+                thread: None,
+            }
+        }
+
+        /// Creates a counter at zero that a background thread advances by `ticks` every `interval`.
+        pub fn running(ticks: u64, interval: Duration) -> Self {
+            let mut ans = Self::new();
+            ans.running.store(true, Ordering::Relaxed);
+            let counter = ans.ticks.clone();
+            let running = ans.running.clone();
+            ans.thread = Some(thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    thread::sleep(interval);
+                    counter.fetch_add(ticks, Ordering::AcqRel);
+                }
+            }));
+            ans
+        }
+
+        #[inline]
+        pub fn read_mtime(&self) -> u64 {
+            self.ticks.load(Ordering::Acquire)
+        }
+
+        #[inline]
+        pub fn write_mtime(&self, val: u64) {
+            self.ticks.store(val, Ordering::Release)
+        }
+
+        #[inline]
+        pub fn advance(&self, ticks: u64) {
+            self.ticks.fetch_add(ticks, Ordering::AcqRel);
+        }
+    }
+
+    impl Default for SimMtime {
+        #[inline]
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Drop for SimMtime {
+        fn drop(&mut self) {
+            self.running.store(false, Ordering::Relaxed);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    /// An MTIMER model with `N` harts whose `mtime` is a [`SimMtime`],
+    /// so the deadline helpers of [`MtimerOps`] can run against a ticking clock.
+    pub struct SimMtimer<const N: usize> {
+        mtime: SimMtime,
+        mtimecmp: [AtomicU64; N],
+    }
+
+    impl<const N: usize> SimMtimer<N> {
+        /// Creates a timer counting `mtime`, with every `mtimecmp` at [`NEVER`].
+        pub fn new(mtime: SimMtime) -> Self {
+            Self {
+                mtime,
+                mtimecmp: [const { AtomicU64::new(NEVER) }; N],
+            }
+        }
+
+        #[inline]
+        pub fn mtime(&self) -> &SimMtime {
+            &self.mtime
+        }
+    }
+
+    impl<const N: usize> MtimerOps for SimMtimer<N> {
+        #[inline]
+        fn read_mtime(&self) -> u64 {
+            self.mtime.read_mtime()
+        }
+
+        #[inline]
+        fn write_mtime(&self, val: u64) {
+            self.mtime.write_mtime(val)
+        }
+
+        #[inline]
+        fn read_mtimecmp(&self, hart_idx: usize) -> u64 {
+            self.mtimecmp[hart_idx].load(Ordering::Acquire)
+        }
+
+        #[inline]
+        fn write_mtimecmp(&self, hart_idx: usize, val: u64) {
+            self.mtimecmp[hart_idx].store(val, Ordering::Release)
+        }
+    }
+
+    impl<const H: usize> SifiveClint<H> {
+        /// Returns harts in `0..hart_count` whose `mtimecmp <= mtime`,
+        /// letting a host test drive an interrupt dispatcher from a mock CLINT deterministically.
+        ///
+        /// No interrupt is raised; harness code decides what firing means.
+        pub fn poll_timer_interrupts(&self, hart_count: usize) -> Vec<usize> {
+            self.expired_timers(0..hart_count).collect()
+        }
+    }
+
+    #[test]
+    fn test_sim_mtime() {
+        let mtime = SimMtime::new();
+        mtime.advance(5);
+        thread::scope(|s| {
+            s.spawn(|| mtime.advance(5));
+        });
+        assert_eq!(mtime.read_mtime(), 10);
+
+        let mtime = SimMtime::running(1, Duration::from_millis(1));
+        while mtime.read_mtime() < 3 {
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn test_sim_mtimer() {
+        let timer = SimMtimer::<2>::new(SimMtime::new());
+        timer.write_mtimecmp(1, 10);
+        assert_eq!(timer.ticks_until_deadline(1), Some(10));
+        assert_eq!(timer.ticks_until_deadline(0), Some(NEVER));
+        timer.mtime().advance(10);
+        assert!(timer.is_timer_expired(1));
+        assert!(!timer.is_timer_expired(0));
+
+        let timer = SimMtimer::<1>::new(SimMtime::running(1, Duration::from_millis(1)));
+        timer.write_mtimecmp(0, timer.read_mtime() + 3);
+        while !timer.is_timer_expired(0) {
+            thread::yield_now();
+        }
+        assert_eq!(timer.ticks_until_deadline(0), None);
+    }
+
+    #[test]
+    fn test_poll_timer_interrupts() {
+        let clint = unsafe { SifiveClint::<4>::new_boxed() };
+        clint.disable_all_timers(4);
+        clint.write_mtimecmp(2, 100);
+        assert!(clint.poll_timer_interrupts(4).is_empty());
+        clint.write_mtime(100);
+        assert_eq!(clint.poll_timer_interrupts(4), [2]);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::SifiveClint;
+use core::{fmt, ops::Range};
+
+/// Register state of one hart captured by [`SifiveClint::debug_snapshot`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HartSnapshot {
+    pub hart_idx: usize,
+    pub msip: bool,
+    pub mtimecmp: u64,
+}
+
+/// Register state of a CLINT captured by [`SifiveClint::debug_snapshot`].
+#[derive(Clone, Copy)]
+pub struct ClintSnapshot {
+    pub mtime: u64,
+    len: usize,
+    harts: [HartSnapshot; ClintSnapshot::CAPACITY],
+}
+
+impl ClintSnapshot {
+    /// Maximum number of harts a snapshot holds.
+    pub const CAPACITY: usize = 16;
+
+    /// Returns the captured harts.
+    #[inline]
+    pub fn harts(&self) -> &[HartSnapshot] {
+        &self.harts[..self.len]
+    }
+}
+
+impl fmt::Debug for ClintSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClintSnapshot")
+            .field("mtime", &self.mtime)
+            .field("harts", &self.harts())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ClintSnapshot {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ClintSnapshot {{ mtime: {=u64}, harts: {} }}",
+            self.mtime,
+            self.harts()
+        )
+    }
+}
+
+impl<const H: usize> fmt::Debug for SifiveClint<H> {
+    /// Prints no register, since reading them is volatile. Use [`SifiveClint::debug_snapshot`] instead.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SifiveClint")
+            .field("harts", &H)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const H: usize> SifiveClint<H> {
+    /// Reads `mtime`, and `msip` and `mtimecmp` of each hart in `harts`, into a printable snapshot.
+    ///
+    /// `harts` is clipped to the device capacity and to the first [`ClintSnapshot::CAPACITY`] harts.
+    pub fn debug_snapshot(&self, harts: Range<usize>) -> ClintSnapshot {
+        let mut ans = ClintSnapshot {
+            mtime: self.read_mtime(),
+            len: 0,
+            harts: [HartSnapshot::default(); ClintSnapshot::CAPACITY],
+        };
+        for hart_idx in harts.take(ClintSnapshot::CAPACITY).take_while(|&i| i < H) {
+            ans.harts[ans.len] = HartSnapshot {
+                hart_idx,
+                msip: self.read_msip(hart_idx),
+                mtimecmp: self.read_mtimecmp(hart_idx),
+            };
+            ans.len += 1;
+        }
+        ans
+    }
+}
+
+#[test]
+fn test_snapshot() {
+    use std::format;
+
+    let clint = unsafe { SifiveClint::<4>::new_boxed() };
+    clint.write_mtime(42);
+    clint.set_msip(2);
+    clint.write_mtimecmp(3, 7);
+    let snapshot = clint.debug_snapshot(2..9);
+    assert_eq!(snapshot.harts().len(), 2);
+    assert_eq!(
+        format!("{snapshot:?}"),
+        "ClintSnapshot { mtime: 42, harts: [\
+        HartSnapshot { hart_idx: 2, msip: true, mtimecmp: 0 }, \
+        HartSnapshot { hart_idx: 3, msip: false, mtimecmp: 7 }] }"
+    );
+    assert_eq!(format!("{clint:?}"), "SifiveClint { harts: 4, .. }");
+}
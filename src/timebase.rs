@@ -0,0 +1,93 @@
+use core::time::Duration;
+
+/// Converts between `mtime` ticks and nanoseconds given the platform `timebase-frequency`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timebase {
+    /// Frequency of `mtime` in Hz, must not be zero.
+    pub freq_hz: u64,
+}
+
+impl Timebase {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+    /// # Panics
+    ///
+    /// Panics if `freq_hz` is zero, failing the build when evaluated in a `const` context.
+    #[inline]
+    pub const fn new(freq_hz: u64) -> Self {
+        assert!(freq_hz != 0, "timebase frequency must not be zero");
+        Self { freq_hz }
+    }
+
+    /// Converts `ticks` into nanoseconds, rounding down and saturating at `u64::MAX`.
+    #[inline]
+    pub const fn ticks_to_nanos(&self, ticks: u64) -> u64 {
+        saturate(ticks as u128 * Self::NANOS_PER_SEC / self.freq_hz as u128)
+    }
+
+    /// Converts `nanos` into ticks, rounding down and saturating at `u64::MAX`.
+    #[inline]
+    pub const fn nanos_to_ticks(&self, nanos: u64) -> u64 {
+        saturate(nanos as u128 * self.freq_hz as u128 / Self::NANOS_PER_SEC)
+    }
+
+    /// Converts `ticks` into a [`Duration`], exactly up to nanosecond precision.
+    #[inline]
+    pub const fn ticks_to_duration(&self, ticks: u64) -> Duration {
+        let secs = ticks / self.freq_hz;
+        let nanos = (ticks % self.freq_hz) as u128 * Self::NANOS_PER_SEC / self.freq_hz as u128;
+        Duration::new(secs, nanos as u32)
+    }
+
+    /// Converts `dur` into ticks, rounding down and saturating at `u64::MAX`.
+    #[inline]
+    pub const fn duration_to_ticks(&self, dur: Duration) -> u64 {
+        saturate(dur.as_nanos().saturating_mul(self.freq_hz as u128) / Self::NANOS_PER_SEC)
+    }
+
+    /// Converts the interval between two `mtime` samples into nanoseconds,
+    /// see [`elapsed_ticks`](crate::elapsed_ticks).
+    #[inline]
+    pub const fn elapsed_nanos(&self, earlier: u64, later: u64) -> u64 {
+        self.ticks_to_nanos(crate::elapsed_ticks(earlier, later))
+    }
+
+    /// Returns the `mtime` value `dur_ns` nanoseconds after `now`, saturating at `u64::MAX`.
+    #[inline]
+    pub const fn deadline_after(&self, now: u64, dur_ns: u64) -> u64 {
+        now.saturating_add(self.nanos_to_ticks(dur_ns))
+    }
+}
+
+#[inline]
+const fn saturate(val: u128) -> u64 {
+    if val > u64::MAX as u128 {
+        u64::MAX
+    } else {
+        val as u64
+    }
+}
+
+#[test]
+fn test_timebase() {
+    let timebase = Timebase::new(10_000_000);
+    assert_eq!(timebase.ticks_to_nanos(3), 300);
+    assert_eq!(timebase.nanos_to_ticks(1_000), 10);
+    assert_eq!(timebase.ticks_to_nanos(u64::MAX), u64::MAX);
+    assert_eq!(timebase.deadline_after(5, 1_000), 15);
+    assert_eq!(timebase.deadline_after(u64::MAX - 1, 1_000), u64::MAX);
+    assert_eq!(timebase.elapsed_nanos(5, 15), 1_000);
+    assert_eq!(
+        timebase.ticks_to_duration(25_000_003),
+        Duration::new(2, 500_000_300)
+    );
+    assert_eq!(timebase.duration_to_ticks(Duration::from_millis(5)), 50_000);
+    assert_eq!(timebase.duration_to_ticks(Duration::MAX), u64::MAX);
+    assert_eq!(timebase.elapsed_nanos(u64::MAX, 9), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "timebase frequency must not be zero")]
+fn test_timebase_zero() {
+    let _ = Timebase::new(0);
+}
@@ -0,0 +1,82 @@
+use crate::{ClintError, SifiveClint};
+
+/// A [`SifiveClint`] checking every hart index against the platform hart count.
+#[derive(Clone, Copy)]
+pub struct ClintView<'a, const H: usize> {
+    clint: &'a SifiveClint<H>,
+    max_harts: usize,
+}
+
+impl<'a, const H: usize> ClintView<'a, H> {
+    /// Creates a view accepting hart indices below `max_harts`, which is clipped to `H`.
+    #[inline]
+    pub const fn new(clint: &'a SifiveClint<H>, max_harts: usize) -> Self {
+        Self {
+            clint,
+            max_harts: if max_harts < H { max_harts } else { H },
+        }
+    }
+
+    #[inline]
+    pub const fn max_harts(&self) -> usize {
+        self.max_harts
+    }
+
+    #[inline]
+    fn check(&self, hart_idx: usize) -> Result<usize, ClintError> {
+        if hart_idx < self.max_harts {
+            Ok(hart_idx)
+        } else {
+            Err(ClintError::HartOutOfRange {
+                hart: hart_idx,
+                max: self.max_harts,
+            })
+        }
+    }
+
+    #[inline]
+    pub fn read_mtime(&self) -> u64 {
+        self.clint.read_mtime()
+    }
+
+    #[inline]
+    pub fn read_mtimecmp(&self, hart_idx: usize) -> Result<u64, ClintError> {
+        self.check(hart_idx).map(|i| self.clint.read_mtimecmp(i))
+    }
+
+    #[inline]
+    pub fn write_mtimecmp(&self, hart_idx: usize, val: u64) -> Result<(), ClintError> {
+        self.check(hart_idx)
+            .map(|i| self.clint.write_mtimecmp(i, val))
+    }
+
+    #[inline]
+    pub fn read_msip(&self, hart_idx: usize) -> Result<bool, ClintError> {
+        self.check(hart_idx).map(|i| self.clint.read_msip(i))
+    }
+
+    #[inline]
+    pub fn set_msip(&self, hart_idx: usize) -> Result<(), ClintError> {
+        self.check(hart_idx).map(|i| self.clint.set_msip(i))
+    }
+
+    #[inline]
+    pub fn clear_msip(&self, hart_idx: usize) -> Result<(), ClintError> {
+        self.check(hart_idx).map(|i| self.clint.clear_msip(i))
+    }
+}
+
+#[test]
+fn test_view() {
+    let clint = unsafe { SifiveClint::<8>::new_boxed() };
+    let view = ClintView::new(&clint, 4);
+    assert_eq!(view.set_msip(3), Ok(()));
+    assert_eq!(view.read_msip(3), Ok(true));
+    assert_eq!(
+        view.set_msip(4),
+        Err(ClintError::HartOutOfRange { hart: 4, max: 4 })
+    );
+    assert!(!clint.read_msip(4));
+    assert!(view.write_mtimecmp(100, 0).is_err());
+    assert_eq!(ClintView::new(&clint, 100).max_harts(), 8);
+}
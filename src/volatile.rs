@@ -0,0 +1,51 @@
+//! Volatile accesses to device registers, which are little-endian.
+//!
+//! The conversions compile to nothing on little-endian targets,
+//! and swap bytes on the rare big-endian RISC-V implementations.
+
+pub(crate) trait ReadLe {
+    type Int;
+
+    /// Volatile-reads the little-endian register at `self`.
+    unsafe fn read_le(self) -> Self::Int;
+}
+
+pub(crate) trait WriteLe {
+    type Int;
+
+    /// Volatile-writes `val` to the little-endian register at `self`.
+    unsafe fn write_le(self, val: Self::Int);
+}
+
+macro_rules! impl_le {
+    ($($int:ty)*) => {$(
+        impl ReadLe for *const $int {
+            type Int = $int;
+
+            #[inline(always)]
+            unsafe fn read_le(self) -> $int {
+                <$int>::from_le(self.read_volatile())
+            }
+        }
+
+        impl ReadLe for *mut $int {
+            type Int = $int;
+
+            #[inline(always)]
+            unsafe fn read_le(self) -> $int {
+                <$int>::from_le(self.read_volatile())
+            }
+        }
+
+        impl WriteLe for *mut $int {
+            type Int = $int;
+
+            #[inline(always)]
+            unsafe fn write_le(self, val: $int) {
+                self.write_volatile(val.to_le())
+            }
+        }
+    )*};
+}
+
+impl_le!(u32 u64);